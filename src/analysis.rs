@@ -0,0 +1,247 @@
+//! How well a player can do in a [`Game`] against a known opponent.
+
+use anyhow::{bail, Result};
+
+use crate::game::{Game, Move};
+
+/// The best score any sequence of moves gets against `opponent`.
+pub fn best_score(game: &Game, opponent: &[Move]) -> u32 {
+    opponent
+        .iter()
+        .map(|&theirs| round_scores(game, theirs).max().unwrap())
+        .sum()
+}
+
+/// The worst score any sequence of moves gets against `opponent`.
+pub fn worst_score(game: &Game, opponent: &[Move]) -> u32 {
+    opponent
+        .iter()
+        .map(|&theirs| round_scores(game, theirs).min().unwrap())
+        .sum()
+}
+
+fn round_scores(game: &Game, theirs: Move) -> impl Iterator<Item = u32> + '_ {
+    game.moves()
+        .map(move |mine| game.score_round((theirs, mine)))
+}
+
+/// Guides up to this many rounds get the exact chance of every total score.
+/// Working it out takes time quadratic in the rounds, so longer guides use a
+/// normal approximation instead.
+const EXACT_ROUNDS: usize = 1000;
+
+/// How likely each total score is.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Distribution {
+    mean: f64,
+    variance: f64,
+    /// The chance of every score from 0 up to the highest possible, for
+    /// short enough guides.
+    exact: Option<Vec<f64>>,
+}
+
+impl Distribution {
+    /// The scores of playing a uniformly random move every round against
+    /// `opponent`.
+    pub fn random_moves(game: &Game, opponent: &[Move]) -> Distribution {
+        let chance = 1.0 / game.moves().count() as f64;
+
+        // rounds are independent, so their means and variances add up
+        let (mut mean, mut variance) = (0.0, 0.0);
+        for &theirs in opponent {
+            let round_mean: f64 = round_scores(game, theirs).map(|s| s as f64 * chance).sum();
+            variance += round_scores(game, theirs)
+                .map(|s| (s as f64 - round_mean).powi(2) * chance)
+                .sum::<f64>();
+            mean += round_mean;
+        }
+
+        let exact = (opponent.len() <= EXACT_ROUNDS).then(|| {
+            let mut probabilities = vec![1.0];
+            for &theirs in opponent {
+                let highest = round_scores(game, theirs).max().unwrap() as usize;
+                let mut next = vec![0.0; probabilities.len() + highest];
+                for (total, &p) in probabilities.iter().enumerate() {
+                    for score in round_scores(game, theirs) {
+                        next[total + score as usize] += p * chance;
+                    }
+                }
+                probabilities = next;
+            }
+            probabilities
+        });
+
+        Distribution {
+            mean,
+            variance,
+            exact,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn probability(&self, score: u32) -> Option<f64> {
+        let exact = self.exact.as_ref()?;
+        Some(exact.get(score as usize).copied().unwrap_or(0.0))
+    }
+
+    /// The chance of scoring less than `score`.
+    pub fn below(&self, score: u32) -> f64 {
+        match &self.exact {
+            Some(probabilities) => probabilities.iter().take(score as usize).sum(),
+            None if self.variance == 0.0 => f64::from(u8::from(self.mean < score as f64)),
+            // scores are whole numbers, so "less than score" means up to score - 1/2
+            None => normal_cdf((score as f64 - 0.5 - self.mean) / self.std_dev()),
+        }
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+}
+
+/// The chance of a standard normal variable being below `x`.
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// The error function, to within 1.5e-7 (Abramowitz and Stegun 7.1.26).
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let y = 1.0 - poly * (-x * x).exp();
+    y.copysign(x)
+}
+
+/// `weights` scaled to add up to 1, so they can be given as counts.
+fn frequencies(game: &Game, weights: &[f64]) -> Result<Vec<f64>> {
+    if weights.len() != game.moves().count() {
+        bail!(
+            "Expected a frequency for each of the {} moves, got {}",
+            game.moves().count(),
+            weights.len()
+        );
+    }
+    if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+        bail!("Frequencies can't be negative");
+    }
+    let total: f64 = weights.iter().sum();
+    if total == 0.0 {
+        bail!("The opponent has to play something");
+    }
+    Ok(weights.iter().map(|w| w / total).collect())
+}
+
+/// The average score of a round playing `mine` against an opponent who picks
+/// their moves with the relative frequencies in `weights`.
+pub fn expected_score(game: &Game, weights: &[f64], mine: Move) -> Result<f64> {
+    Ok(game
+        .moves()
+        .zip(frequencies(game, weights)?)
+        .map(|(theirs, p)| game.score_round((theirs, mine)) as f64 * p)
+        .sum())
+}
+
+/// The move with the best [`expected_score`] against `weights`, and that
+/// score. Ties go to the move earlier in the cycle.
+pub fn best_response(game: &Game, weights: &[f64]) -> Result<(Move, f64)> {
+    let mut best: Option<(Move, f64)> = None;
+    for mine in game.moves() {
+        let score = expected_score(game, weights, mine)?;
+        if best.is_none_or(|(_, best)| score > best) {
+            best = Some((mine, score));
+        }
+    }
+    Ok(best.unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROCK: Move = Move(0);
+    const PAPER: Move = Move(1);
+    const SCISSORS: Move = Move(2);
+
+    #[test]
+    fn best_and_worst() {
+        let game = Game::rock_paper_scissors();
+        let opponent = [ROCK, PAPER, SCISSORS];
+        // paper, scissors and rock win for 8, 9 and 7
+        assert_eq!(24, best_score(&game, &opponent));
+        // scissors, rock and paper lose for 3, 1 and 2
+        assert_eq!(6, worst_score(&game, &opponent));
+        assert_eq!(0, best_score(&game, &[]));
+    }
+
+    #[test]
+    fn random_moves() {
+        let game = Game::rock_paper_scissors();
+        let one = Distribution::random_moves(&game, &[ROCK]);
+        // scissors loses for 3, rock draws for 4, paper wins for 8
+        for score in [3, 4, 8] {
+            assert!((one.probability(score).unwrap() - 1.0 / 3.0).abs() < 1e-9);
+        }
+        assert_eq!(0.0, one.probability(5).unwrap());
+        assert!((one.mean() - 5.0).abs() < 1e-9);
+        assert!((one.below(4) - 1.0 / 3.0).abs() < 1e-9);
+
+        let opponent = [ROCK, PAPER, SCISSORS, ROCK, ROCK];
+        let many = Distribution::random_moves(&game, &opponent);
+        let total: f64 = (0..100).map(|score| many.probability(score).unwrap()).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert_eq!(0.0, many.below(worst_score(&game, &opponent)));
+        assert!((many.below(best_score(&game, &opponent) + 1) - 1.0).abs() < 1e-9);
+        // each round is worth 5 on average, whatever the opponent plays
+        assert!((many.mean() - 25.0).abs() < 1e-9);
+        assert!(many.std_dev() > 0.0);
+    }
+
+    #[test]
+    fn long_guides() {
+        let game = Game::rock_paper_scissors();
+        let opponent = vec![ROCK; 100_000];
+        let many = Distribution::random_moves(&game, &opponent);
+        assert_eq!(None, many.probability(0));
+        assert!((many.mean() - 500_000.0).abs() < 1e-6);
+        // every round is 3, 4 or 8 a third of the time each
+        let variance = (4.0 + 1.0 + 9.0) / 3.0 * 100_000.0;
+        assert!((many.std_dev() - f64::sqrt(variance)).abs() < 1e-6);
+        assert!((many.below(500_000) - 0.5).abs() < 1e-2);
+        assert!(many.below(worst_score(&game, &opponent)) < 1e-9);
+        assert!(many.below(best_score(&game, &opponent)) > 1.0 - 1e-9);
+
+        // the approximation agrees with the exact chances where both exist
+        let opponent = &opponent[..EXACT_ROUNDS];
+        let exact = Distribution::random_moves(&game, opponent);
+        let approximate = Distribution {
+            exact: None,
+            ..exact.clone()
+        };
+        for score in [4900, 5000, 5050, 5100] {
+            assert!((exact.below(score) - approximate.below(score)).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn against_a_profile() {
+        let game = Game::rock_paper_scissors();
+        assert!((expected_score(&game, &[1.0, 0.0, 0.0], PAPER).unwrap() - 8.0).abs() < 1e-9);
+        assert!((expected_score(&game, &[1.0, 1.0, 1.0], ROCK).unwrap() - 4.0).abs() < 1e-9);
+
+        // counts work as well as frequencies
+        let (mine, score) = best_response(&game, &[5.0, 3.0, 2.0]).unwrap();
+        assert_eq!(PAPER, mine);
+        assert!((score - 5.9).abs() < 1e-9);
+        assert_eq!(SCISSORS, best_response(&game, &[0.0, 1.0, 0.0]).unwrap().0);
+
+        assert!(expected_score(&game, &[1.0, 1.0], ROCK).is_err());
+        assert!(expected_score(&game, &[0.0, 0.0, 0.0], ROCK).is_err());
+        assert!(expected_score(&game, &[1.0, -1.0, 1.0], ROCK).is_err());
+    }
+}
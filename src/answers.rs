@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use num::BigInt;
+
+use crate::solution::Answer;
+
+/// Known answers, read from a small TOML file with one table per day:
+///
+/// ```toml
+/// [day5]
+/// part1 = "CMZ"
+/// part2 = 12
+/// ```
+#[derive(Debug, PartialEq, Default)]
+pub struct Answers(HashMap<(u32, u32), Answer>);
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Status {
+    Pass,
+    Fail { expected: Answer },
+    Missing,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected } => write!(f, "FAIL, expected {}", expected),
+            Status::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+fn parse_value(s: &str) -> Result<Answer> {
+    if let Some(quoted) = s.strip_prefix('"') {
+        let text = quoted
+            .strip_suffix('"')
+            .context("Unterminated string")?
+            .replace("\\\"", "\"")
+            .replace("\\\\", "\\");
+        return Ok(Answer::Text(text));
+    }
+
+    let number: BigInt = s
+        .replace('_', "")
+        .parse()
+        .with_context(|| format!("Invalid answer `{}`", s))?;
+    Ok(Answer::number(number))
+}
+
+fn parse_number(s: &str, prefix: &str) -> Option<u32> {
+    s.strip_prefix(prefix)?.parse().ok()
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Answers> {
+        Answers::parse_from(input, None)
+    }
+
+    /// Parses answers for a single `day`, which may leave out the `[dayN]` table.
+    #[cfg(test)]
+    pub fn parse_day(input: &str, day: u32) -> Result<Answers> {
+        Answers::parse_from(input, Some(day))
+    }
+
+    fn parse_from(input: &str, mut day: Option<u32>) -> Result<Answers> {
+        let mut answers = HashMap::new();
+
+        for (i, line) in input.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(table) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                day = Some(parse_number(table.trim(), "day").with_context(|| {
+                    format!(
+                        "Line {}: expected a `[dayN]` table, got `{}`",
+                        line_no, line
+                    )
+                })?);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .with_context(|| format!("Line {}: expected `partN = answer`", line_no))?;
+            let part = parse_number(key.trim(), "part")
+                .filter(|part| (1..=2).contains(part))
+                .with_context(|| format!("Line {}: unknown key `{}`", line_no, key.trim()))?;
+            let Some(day) = day else {
+                bail!("Line {}: answer outside of a `[dayN]` table", line_no);
+            };
+            let value = parse_value(value.trim()).with_context(|| format!("Line {}", line_no))?;
+
+            answers.insert((day, part), value);
+        }
+
+        Ok(Answers(answers))
+    }
+
+    pub fn load(path: &Path) -> Result<Answers> {
+        let input = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read answers from {}", path.display()))?;
+        Answers::parse(&input).with_context(|| format!("Invalid answers file {}", path.display()))
+    }
+
+    pub fn get(&self, day: u32, part: u32) -> Option<&Answer> {
+        self.0.get(&(day, part))
+    }
+
+    pub fn check(&self, day: u32, part: u32, answer: &Answer) -> Status {
+        match self.get(day, part) {
+            Some(expected) if expected == answer => Status::Pass,
+            Some(expected) => Status::Fail {
+                expected: expected.clone(),
+            },
+            None => Status::Missing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_test() {
+        let input = "# sample answers
+[day1]
+part1 = 24_000
+part2 = 45000
+
+[ day5 ]
+part1 = \"CMZ\"
+";
+        let answers = Answers::parse(input).unwrap();
+        assert_eq!(Some(&Answer::Number(24000)), answers.get(1, 1));
+        assert_eq!(Some(&Answer::Number(45000)), answers.get(1, 2));
+        assert_eq!(Some(&Answer::from("CMZ")), answers.get(5, 1));
+        assert_eq!(None, answers.get(5, 2));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Answers::parse("part1 = 3").is_err());
+        assert!(Answers::parse("[dayx]").is_err());
+        assert!(Answers::parse("[day1]\npart3 = 3").is_err());
+        assert!(Answers::parse("[day1]\npart1 = \"CMZ").is_err());
+        assert!(Answers::parse("[day1]\npart1 = CMZ").is_err());
+    }
+
+    #[test]
+    fn parse_day_test() {
+        let answers = Answers::parse_day("part1 = 7\npart2 = 19", 6).unwrap();
+        assert_eq!(Some(&Answer::Number(7)), answers.get(6, 1));
+        assert_eq!(Some(&Answer::Number(19)), answers.get(6, 2));
+        assert!(Answers::parse("part1 = 7").is_err());
+    }
+
+    #[test]
+    fn wide_answers() {
+        let answers =
+            Answers::parse("[day1]\npart1 = 18_446_744_073_709_551_616\npart2 = 45000").unwrap();
+        let wide = Answer::number(u64::MAX as u128 + 1);
+        assert_eq!(Some(&wide), answers.get(1, 1));
+        assert_eq!(Status::Pass, answers.check(1, 1, &wide));
+        assert_eq!(Some(&Answer::Number(45000)), answers.get(1, 2));
+    }
+
+    #[test]
+    fn check_test() {
+        let answers = Answers::parse("[day2]\npart1 = 15").unwrap();
+        assert_eq!(Status::Pass, answers.check(2, 1, &Answer::Number(15)));
+        assert_eq!(
+            Status::Fail {
+                expected: Answer::Number(15)
+            },
+            answers.check(2, 1, &Answer::Number(14))
+        );
+        assert_eq!(Status::Missing, answers.check(2, 2, &Answer::Number(12)));
+    }
+}
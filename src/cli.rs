@@ -0,0 +1,583 @@
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+
+use crate::day1::Width;
+use crate::input::InputSource;
+
+pub const USAGE: &str = "Usage: aoc [DAYS] [OPTIONS]
+       aoc new DAY
+
+  DAYS                a single day (`5`) or an inclusive range (`3..6`, `3..=6`)
+  -p, --part N        only run part 1 or part 2
+  -i, --input FILE    read the puzzle input from FILE, or from stdin if FILE is `-`
+      --input-str S   use S as the puzzle input
+      --input-dir DIR directory holding the default inputs
+                      (defaults to $AOC_INPUT_DIR, then `input`)
+      --fetch URL     download inputs missing from the input directory from
+                      URL/{year}/day/{day}/input, sending $AOC_SESSION as the
+                      session cookie
+      --verify        check every answer against the answers file
+      --answers FILE  answers file to verify against
+                      (defaults to `answers.toml` in the input directory)
+      --bench N       run every solution N times and report timings instead
+      --strict        reject inputs with CRLF line endings, stray whitespace or
+                      extra blank lines instead of cleaning them up
+      --stats         report statistics about the input instead, for days that
+                      have them
+      --stream        read the input a line at a time, for days that support it,
+                      so inputs larger than memory can be solved
+      --symbols FILE  read day 2 strategy guides with the letters in FILE
+      --find-symbols SCORE
+                      print every way of reading our column of the day 2
+                      strategy guide as moves that scores SCORE in part 1
+      --tournament N  play bots and both columns of the day 2 strategy guide
+                      against each other for N rounds and print a league table
+      --width W       count day 1 calories in 64 (default) or 128 bit numbers,
+                      or in numbers of any size with `big`
+      --group-size N  split day 3 rucksacks into groups of N instead of 3
+      --explain       show how every line of the day 2 strategy guide is scored
+      --format FMT    print results as `text` (default) or `json`, or
+                      explanations as `text` or `csv`";
+
+#[derive(Debug, PartialEq)]
+pub struct Args {
+    pub days: RangeInclusive<u32>,
+    pub part: Option<u32>,
+    pub input: InputSource,
+    pub input_dir: Option<PathBuf>,
+    pub fetch: Option<String>,
+    pub verify: bool,
+    pub answers: Option<PathBuf>,
+    pub bench: Option<usize>,
+    pub stream: bool,
+    pub stats: bool,
+    pub strict: bool,
+    pub symbols: Option<PathBuf>,
+    pub find_symbols: Option<u32>,
+    pub tournament: Option<usize>,
+    pub explain: bool,
+    pub width: Option<Width>,
+    pub group_size: Option<usize>,
+    pub format: Format,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            days: 1..=25,
+            part: None,
+            input: InputSource::Default,
+            input_dir: None,
+            fetch: None,
+            verify: false,
+            answers: None,
+            bench: None,
+            stream: false,
+            stats: false,
+            strict: false,
+            symbols: None,
+            find_symbols: None,
+            tournament: None,
+            explain: false,
+            width: None,
+            group_size: None,
+            format: Format::Text,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    Text,
+    Json,
+    /// Only for `--explain`.
+    Csv,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(Box<Args>),
+    /// Scaffold a module for a new day.
+    New(u32),
+    Help,
+}
+
+fn parse_day(s: &str) -> Result<u32> {
+    let day = s
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid day `{}`", s))?;
+    if !(1..=25).contains(&day) {
+        bail!("Day {} is outside 1..=25", day);
+    }
+    Ok(day)
+}
+
+fn parse_days(s: &str) -> Result<RangeInclusive<u32>> {
+    let days = match s.split_once("..") {
+        Some((start, end)) => {
+            let end = end.strip_prefix('=').unwrap_or(end);
+            parse_day(start)?..=parse_day(end)?
+        }
+        None => {
+            let day = parse_day(s)?;
+            day..=day
+        }
+    };
+
+    if days.is_empty() {
+        bail!("Empty day range `{}`", s);
+    }
+    Ok(days)
+}
+
+fn parse_part(s: &str) -> Result<u32> {
+    match s {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => bail!("Invalid part `{}`, expected 1 or 2", s),
+    }
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command> {
+    let mut parsed = Args::default();
+    let mut days = None;
+
+    let mut args = args.into_iter().peekable();
+    if args.peek().is_some_and(|arg| arg == "new") {
+        args.next();
+        let day = args.next().context("Missing day for `aoc new`")?;
+        if let Some(arg) = args.next() {
+            bail!("Unexpected argument `{}`", arg);
+        }
+        return Ok(Command::New(parse_day(&day)?));
+    }
+
+    while let Some(arg) = args.next() {
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_owned())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .with_context(|| format!("Missing value for {}", name))
+        };
+
+        match name {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-p" | "--part" => parsed.part = Some(parse_part(&value()?)?),
+            "-i" | "--input" => {
+                parsed.input = match value()?.as_str() {
+                    "-" => InputSource::Stdin,
+                    path => InputSource::File(path.into()),
+                }
+            }
+            "--input-str" => parsed.input = InputSource::Inline(value()?),
+            "--input-dir" => parsed.input_dir = Some(value()?.into()),
+            "--fetch" => parsed.fetch = Some(value()?),
+            "--verify" => parsed.verify = true,
+            "--answers" => {
+                parsed.verify = true;
+                parsed.answers = Some(value()?.into());
+            }
+            "--bench" => {
+                let runs = value()?;
+                parsed.bench = match runs.parse() {
+                    Ok(0) | Err(_) => bail!("Invalid number of runs `{}`", runs),
+                    Ok(runs) => Some(runs),
+                }
+            }
+            "--stream" => parsed.stream = true,
+            "--stats" => parsed.stats = true,
+            "--strict" => parsed.strict = true,
+            "--symbols" => parsed.symbols = Some(value()?.into()),
+            "--find-symbols" => {
+                let score = value()?;
+                parsed.find_symbols = Some(
+                    score
+                        .parse()
+                        .with_context(|| format!("Invalid score `{}`", score))?,
+                );
+            }
+            "--tournament" => {
+                let rounds = value()?;
+                parsed.tournament = match rounds.parse() {
+                    Ok(0) | Err(_) => bail!("Invalid number of rounds `{}`", rounds),
+                    Ok(rounds) => Some(rounds),
+                }
+            }
+            "--explain" => parsed.explain = true,
+            "--width" => {
+                parsed.width = match value()?.as_str() {
+                    "64" => Some(Width::U64),
+                    "128" => Some(Width::U128),
+                    "big" => Some(Width::Big),
+                    width => bail!("Unknown width `{}`, expected 64, 128 or big", width),
+                }
+            }
+            "--group-size" => {
+                let size = value()?;
+                parsed.group_size = match size.parse() {
+                    Ok(0) | Err(_) => bail!("Invalid group size `{}`", size),
+                    Ok(size) => Some(size),
+                }
+            }
+            "--format" => {
+                parsed.format = match value()?.as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    "csv" => Format::Csv,
+                    format => bail!("Unknown format `{}`, expected text, json or csv", format),
+                }
+            }
+            "-" => bail!("Unexpected argument `-`, use `--input -` to read stdin"),
+            _ if name.starts_with('-') => bail!("Unknown option `{}`", name),
+            _ if days.is_some() => bail!("Unexpected argument `{}`", arg),
+            _ => days = Some(parse_days(&arg)?),
+        }
+    }
+
+    if let Some(days) = days {
+        parsed.days = days;
+    }
+
+    if parsed.bench.is_some() && parsed.verify {
+        bail!("--bench and --verify can't be combined");
+    }
+
+    if parsed.bench.is_some() && parsed.format == Format::Json {
+        bail!("--bench only supports text output");
+    }
+
+    if parsed.bench.is_some() && parsed.stream {
+        bail!("--bench and --stream can't be combined");
+    }
+
+    if parsed.stats && (parsed.bench.is_some() || parsed.verify || parsed.stream) {
+        bail!("--stats can't be combined with --bench, --verify or --stream");
+    }
+
+    if parsed.stats && parsed.format == Format::Json {
+        bail!("--stats only supports text output");
+    }
+
+    if parsed.strict && parsed.stream {
+        bail!("--strict and --stream can't be combined");
+    }
+
+    let day2_only = [
+        ("--symbols", parsed.symbols.is_some()),
+        ("--find-symbols", parsed.find_symbols.is_some()),
+        ("--tournament", parsed.tournament.is_some()),
+        ("--explain", parsed.explain),
+    ];
+    for (flag, _) in day2_only.iter().filter(|(_, set)| *set) {
+        if parsed.days != (2..=2) {
+            bail!("{} only applies to day 2", flag);
+        }
+    }
+
+    if parsed.width.is_some() && parsed.days != (1..=1) {
+        bail!("--width only applies to day 1");
+    }
+
+    if parsed.group_size.is_some() && parsed.days != (3..=3) {
+        bail!("--group-size only applies to day 3");
+    }
+
+    // these print something else instead of the answers, so they don't mix
+    // with any other mode
+    let exclusive = [
+        ("--find-symbols", parsed.find_symbols.is_some()),
+        ("--tournament", parsed.tournament.is_some()),
+        ("--explain", parsed.explain),
+    ];
+    let others = [
+        ("--bench", parsed.bench.is_some()),
+        ("--verify", parsed.verify),
+        ("--stats", parsed.stats),
+        ("--stream", parsed.stream),
+    ];
+    for (flag, _) in exclusive.iter().filter(|(_, set)| *set) {
+        if let Some((other, _)) = others
+            .iter()
+            .chain(&exclusive)
+            .find(|(other, set)| *set && other != flag)
+        {
+            bail!("{} and {} can't be combined", flag, other);
+        }
+        if parsed.format == Format::Json {
+            bail!("{} only supports text output", flag);
+        }
+    }
+
+    if parsed.format == Format::Csv && !parsed.explain {
+        bail!("--format csv only applies to --explain");
+    }
+
+    // every part reads the input again, which stdin only allows once
+    if parsed.stream && parsed.input == InputSource::Stdin && parsed.part.is_none() {
+        bail!("Streaming from stdin needs --part");
+    }
+
+    if parsed.input != InputSource::Default && parsed.days.start() != parsed.days.end() {
+        bail!("An explicit input needs a single day to run");
+    }
+
+    Ok(Command::Run(Box::new(parsed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command> {
+        parse_args(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn no_args_runs_everything() {
+        assert_eq!(Command::Run(Box::default()), parse(&[]).unwrap());
+    }
+
+    #[test]
+    fn single_day_and_part() {
+        assert_eq!(
+            Command::Run(Box::new(Args {
+                days: 5..=5,
+                part: Some(2),
+                ..Args::default()
+            })),
+            parse(&["5", "--part", "2"]).unwrap()
+        );
+        assert_eq!(
+            Command::Run(Box::new(Args {
+                days: 5..=5,
+                part: Some(1),
+                ..Args::default()
+            })),
+            parse(&["-p", "1", "5"]).unwrap()
+        );
+        assert_eq!(
+            Command::Run(Box::new(Args {
+                part: Some(2),
+                ..Args::default()
+            })),
+            parse(&["--part=2"]).unwrap()
+        );
+    }
+
+    #[test]
+    fn day_ranges() {
+        assert_eq!(3..=6, parse_days("3..6").unwrap());
+        assert_eq!(3..=6, parse_days("3..=6").unwrap());
+        assert!(parse_days("6..3").is_err());
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("x").is_err());
+    }
+
+    #[test]
+    fn input_sources() {
+        let input = |args: &[&str]| match parse(args).unwrap() {
+            Command::Run(args) => args.input,
+            _ => unreachable!(),
+        };
+        assert_eq!(InputSource::Default, input(&["2"]));
+        assert_eq!(InputSource::Stdin, input(&["2", "--input", "-"]));
+        assert_eq!(
+            InputSource::File("mine/day2.txt".into()),
+            input(&["2", "-i", "mine/day2.txt"])
+        );
+        assert_eq!(
+            InputSource::Inline("A Y".to_owned()),
+            input(&["2", "--input-str=A Y"])
+        );
+
+        assert!(parse(&["--input", "-"]).is_err());
+        assert!(parse(&["3..4", "--input", "-"]).is_err());
+    }
+
+    #[test]
+    fn input_dir() {
+        match parse(&["--input-dir", "alice"]).unwrap() {
+            Command::Run(args) => assert_eq!(Some(PathBuf::from("alice")), args.input_dir),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn fetch() {
+        match parse(&["7", "--fetch", "http://localhost:8080"]).unwrap() {
+            Command::Run(args) => assert_eq!(Some("http://localhost:8080".to_owned()), args.fetch),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn verify() {
+        match parse(&["--verify"]).unwrap() {
+            Command::Run(args) => {
+                assert!(args.verify);
+                assert_eq!(None, args.answers);
+            }
+            _ => unreachable!(),
+        }
+        match parse(&["--answers", "mine.toml"]).unwrap() {
+            Command::Run(args) => {
+                assert!(args.verify);
+                assert_eq!(Some(PathBuf::from("mine.toml")), args.answers);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn bench() {
+        match parse(&["2", "--bench", "100"]).unwrap() {
+            Command::Run(args) => assert_eq!(Some(100), args.bench),
+            _ => unreachable!(),
+        }
+        assert!(parse(&["--bench", "0"]).is_err());
+        assert!(parse(&["--bench", "x"]).is_err());
+        assert!(parse(&["--bench", "3", "--verify"]).is_err());
+    }
+
+    #[test]
+    fn stream() {
+        match parse(&["1", "--stream", "-i", "big.txt"]).unwrap() {
+            Command::Run(args) => assert!(args.stream),
+            _ => unreachable!(),
+        }
+        assert!(parse(&["1", "--stream", "-i", "-", "-p", "2"]).is_ok());
+        assert!(parse(&["1", "--stream", "-i", "-"]).is_err());
+        assert!(parse(&["--stream", "--bench", "3"]).is_err());
+    }
+
+    #[test]
+    fn stats() {
+        match parse(&["1", "--stats"]).unwrap() {
+            Command::Run(args) => assert!(args.stats),
+            _ => unreachable!(),
+        }
+        assert!(parse(&["--stats", "--verify"]).is_err());
+        assert!(parse(&["--stats", "--bench", "2"]).is_err());
+        assert!(parse(&["--stats", "--stream"]).is_err());
+        assert!(parse(&["--stats", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn strict() {
+        match parse(&["1", "--strict"]).unwrap() {
+            Command::Run(args) => assert!(args.strict),
+            _ => unreachable!(),
+        }
+        assert!(parse(&["--strict", "--stream"]).is_err());
+    }
+
+    #[test]
+    fn symbols() {
+        match parse(&["2", "--symbols", "letters.toml", "--find-symbols", "15"]).unwrap() {
+            Command::Run(args) => {
+                assert_eq!(Some(PathBuf::from("letters.toml")), args.symbols);
+                assert_eq!(Some(15), args.find_symbols);
+            }
+            _ => unreachable!(),
+        }
+        assert!(parse(&["--symbols", "letters.toml"]).is_err());
+        assert!(parse(&["3", "--find-symbols", "15"]).is_err());
+        assert!(parse(&["2", "--find-symbols", "x"]).is_err());
+        assert!(parse(&["2", "--find-symbols", "15", "--verify"]).is_err());
+        assert!(parse(&["2", "--find-symbols", "15", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn tournament() {
+        match parse(&["2", "--tournament", "1000"]).unwrap() {
+            Command::Run(args) => assert_eq!(Some(1000), args.tournament),
+            _ => unreachable!(),
+        }
+        assert!(parse(&["2", "--symbols", "letters.toml", "--tournament", "10"]).is_ok());
+        assert!(parse(&["--tournament", "10"]).is_err());
+        assert!(parse(&["2", "--tournament", "0"]).is_err());
+        assert!(parse(&["2", "--tournament", "10", "--stats"]).is_err());
+        assert!(parse(&["2", "--tournament", "10", "--find-symbols", "15"]).is_err());
+        assert!(parse(&["2", "--tournament", "10", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn explain() {
+        match parse(&["2", "--explain", "--format", "csv"]).unwrap() {
+            Command::Run(args) => {
+                assert!(args.explain);
+                assert_eq!(Format::Csv, args.format);
+            }
+            _ => unreachable!(),
+        }
+        assert!(parse(&["2", "--explain", "-p", "2"]).is_ok());
+        assert!(parse(&["3", "--explain"]).is_err());
+        assert!(parse(&["2", "--explain", "--format", "json"]).is_err());
+        assert!(parse(&["2", "--explain", "--tournament", "5"]).is_err());
+        assert!(parse(&["2", "--format", "csv"]).is_err());
+    }
+
+    #[test]
+    fn width() {
+        match parse(&["1", "--width", "big", "--stream"]).unwrap() {
+            Command::Run(args) => assert_eq!(Some(Width::Big), args.width),
+            _ => unreachable!(),
+        }
+        match parse(&["1", "--width=128"]).unwrap() {
+            Command::Run(args) => assert_eq!(Some(Width::U128), args.width),
+            _ => unreachable!(),
+        }
+        assert!(parse(&["--width", "big"]).is_err());
+        assert!(parse(&["1", "--width", "32"]).is_err());
+    }
+
+    #[test]
+    fn group_size() {
+        match parse(&["3", "--group-size", "4", "--stream"]).unwrap() {
+            Command::Run(args) => assert_eq!(Some(4), args.group_size),
+            _ => unreachable!(),
+        }
+        assert!(parse(&["--group-size", "4"]).is_err());
+        assert!(parse(&["3", "--group-size", "0"]).is_err());
+        assert!(parse(&["3", "--group-size", "x"]).is_err());
+    }
+
+    #[test]
+    fn format() {
+        match parse(&["--format", "json"]).unwrap() {
+            Command::Run(args) => assert_eq!(Format::Json, args.format),
+            _ => unreachable!(),
+        }
+        match parse(&["--format=text"]).unwrap() {
+            Command::Run(args) => assert_eq!(Format::Text, args.format),
+            _ => unreachable!(),
+        }
+        assert!(parse(&["--format", "xml"]).is_err());
+        assert!(parse(&["--format", "json", "--bench", "2"]).is_err());
+    }
+
+    #[test]
+    fn new_day() {
+        assert_eq!(Command::New(7), parse(&["new", "7"]).unwrap());
+        assert!(parse(&["new"]).is_err());
+        assert!(parse(&["new", "26"]).is_err());
+        assert!(parse(&["new", "7", "8"]).is_err());
+    }
+
+    #[test]
+    fn invalid_args() {
+        assert!(parse(&["--part", "3"]).is_err());
+        assert!(parse(&["--part"]).is_err());
+        assert!(parse(&["1", "2"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+        assert_eq!(Command::Help, parse(&["4", "--help"]).unwrap());
+    }
+}
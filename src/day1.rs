@@ -1,126 +1,777 @@
-#[derive(PartialEq, Debug)]
-struct Elf {
-    calories: u32,
+use std::any::type_name;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fmt::{self, Display};
+use std::io::BufRead;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use num::{CheckedAdd, ToPrimitive, Zero};
+
+use crate::parse::{for_each_raw_line, ParseError};
+use crate::solution::{Answer, Solution, Streaming};
+
+/// A type to count calories in. Sums are checked, so a type that is too
+/// small gives an error instead of a wrong answer; `num::BigUint` never does.
+pub trait Calories: Clone + Ord + FromStr + Zero + CheckedAdd + ToPrimitive + Display {}
+
+impl<T: Clone + Ord + FromStr + Zero + CheckedAdd + ToPrimitive + Display> Calories for T {}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Elf<C> {
+    /// Position among the elves in the input, counting from 0.
+    index: usize,
+    items: Vec<C>,
+    calories: C,
 }
 
-fn parse_elves(input: &str) -> Vec<Elf> {
-    input
-        .split_terminator("\n\n")
-        .map(|section| {
-            let calories = section
-                .lines()
-                .map(|line| line.parse::<u32>().unwrap_or(0))
-                .sum();
-            Elf { calories }
-        })
-        .collect()
+/// How forgiving parsing is about the layout of the calorie list.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Mode {
+    /// Only numbers, with single blank lines between elves and `\n` line endings.
+    Strict,
+    /// Also accepts `\r\n` line endings, whitespace around numbers and runs of
+    /// blank lines, counting what it had to clean up.
+    #[default]
+    Lenient,
+}
+
+/// What lenient parsing cleaned up.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Normalized {
+    pub crlf: usize,
+    /// Lines with whitespace around them, not counting a `\r` before the newline.
+    pub trimmed: usize,
+    /// Blank lines that didn't separate two elves.
+    pub extra_blank: usize,
 }
-fn most_calories(elves: &[Elf]) -> u32 {
-    elves
+
+impl Normalized {
+    pub fn is_empty(&self) -> bool {
+        *self == Normalized::default()
+    }
+}
+
+impl Display for Normalized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts: Vec<String> = [
+            (self.crlf, "CRLF line ending", "CRLF line endings"),
+            (
+                self.trimmed,
+                "line with extra whitespace",
+                "lines with extra whitespace",
+            ),
+            (self.extra_blank, "extra blank line", "extra blank lines"),
+        ]
         .iter()
-        .max_by_key(|&e| e.calories)
-        .map_or(0, |e| e.calories)
+        .filter(|&&(n, _, _)| n > 0)
+        .map(|&(n, one, many)| format!("{} {}", n, if n == 1 { one } else { many }))
+        .collect();
+
+        if counts.is_empty() {
+            write!(f, "nothing normalized")
+        } else {
+            write!(f, "normalized {}", counts.join(", "))
+        }
+    }
+}
+
+/// Groups calorie counts into elves one line at a time.
+struct ElfParser<C> {
+    mode: Mode,
+    /// Whether finished elves get their items, or only their total.
+    keep_items: bool,
+    items: Vec<C>,
+    /// How many items the current elf has, kept or not.
+    item_count: usize,
+    calories: C,
+    /// How many elves have been finished so far.
+    elves: usize,
+    normalized: Normalized,
+}
+
+impl<C: Calories> ElfParser<C> {
+    fn new(mode: Mode) -> ElfParser<C> {
+        ElfParser {
+            mode,
+            keep_items: true,
+            items: vec![],
+            item_count: 0,
+            calories: C::zero(),
+            elves: 0,
+            normalized: Normalized::default(),
+        }
+    }
+
+    /// Leaves out the items of finished elves, so memory doesn't grow with
+    /// the biggest elf.
+    fn keep_items(mut self, keep_items: bool) -> ElfParser<C> {
+        self.keep_items = keep_items;
+        self
+    }
+
+    /// Takes the next line without its `\n`, returning the elf it finished if
+    /// it was a separator.
+    fn line(&mut self, raw: &str) -> Result<Option<Elf<C>>, ParseError> {
+        let line = match self.mode {
+            Mode::Strict => {
+                if let Some(content) = raw.strip_suffix('\r') {
+                    return Err(ParseError::missing(content, "Windows line ending"));
+                }
+                let trimmed = raw.trim_end();
+                if trimmed.len() < raw.len() {
+                    return Err(ParseError::new(
+                        raw,
+                        &raw[trimmed.len()..],
+                        "Trailing whitespace",
+                    ));
+                }
+                raw
+            }
+            Mode::Lenient => {
+                let content = match raw.strip_suffix('\r') {
+                    Some(content) => {
+                        self.normalized.crlf += 1;
+                        content
+                    }
+                    None => raw,
+                };
+                let trimmed = content.trim();
+                if trimmed.len() < content.len() {
+                    self.normalized.trimmed += 1;
+                }
+                trimmed
+            }
+        };
+
+        if line.is_empty() {
+            let elf = self.finish();
+            if elf.is_none() {
+                match self.mode {
+                    Mode::Strict => return Err(ParseError::new(raw, raw, "Extra blank line")),
+                    Mode::Lenient => self.normalized.extra_blank += 1,
+                }
+            }
+            return Ok(elf);
+        }
+
+        let item: C = line.parse().map_err(|_| {
+            if line.bytes().all(|b| b.is_ascii_digit()) {
+                ParseError::new(
+                    raw,
+                    line,
+                    format!("Calorie count too big for {}", type_name::<C>()),
+                )
+            } else {
+                ParseError::new(raw, line, "Invalid calorie count")
+            }
+        })?;
+        self.calories = self.calories.checked_add(&item).ok_or_else(|| {
+            ParseError::new(
+                raw,
+                line,
+                format!("Elf's calories overflow {}", type_name::<C>()),
+            )
+        })?;
+        self.item_count += 1;
+        if self.keep_items {
+            self.items.push(item);
+        }
+        Ok(None)
+    }
+
+    fn finish(&mut self) -> Option<Elf<C>> {
+        if self.item_count == 0 {
+            return None;
+        }
+        self.item_count = 0;
+        self.elves += 1;
+        Some(Elf {
+            index: self.elves - 1,
+            items: std::mem::take(&mut self.items),
+            calories: std::mem::replace(&mut self.calories, C::zero()),
+        })
+    }
 }
 
-fn top_three_calories(elves: &mut [Elf]) -> u32 {
-    elves.sort_by_key(|e| std::cmp::Reverse(e.calories));
+fn parse_elves<C: Calories>(
+    input: &str,
+    mode: Mode,
+) -> Result<(Vec<Elf<C>>, Normalized), ParseError> {
+    let mut parser = ElfParser::new(mode);
+    let mut elves = vec![];
 
-    elves[0..3].iter().map(|e| e.calories).sum()
+    // not `lines()`, which would hide `\r\n` line endings
+    let lines = input
+        .split_inclusive('\n')
+        .map(|line| line.strip_suffix('\n').unwrap_or(line));
+    for (i, line) in lines.enumerate() {
+        elves.extend(parser.line(line).map_err(|e| e.at_line(i + 1))?);
+    }
+    elves.extend(parser.finish());
+
+    Ok((elves, parser.normalized))
 }
 
-pub fn part1() {
-    let input =
-        std::fs::read_to_string("input/day1_part1.txt").expect("Need to provide puzzle input");
+/// Streams the elves in `input` to `f` as each one is finished. The elves
+/// come without their items, so only one line is held at a time.
+fn stream_elves<C: Calories>(
+    input: &mut dyn BufRead,
+    mode: Mode,
+    mut f: impl FnMut(Elf<C>),
+) -> Result<()> {
+    let mut parser = ElfParser::new(mode).keep_items(false);
+    for_each_raw_line(input, |line| {
+        if let Some(elf) = parser.line(line)? {
+            f(elf);
+        }
+        Ok(())
+    })?;
+    if let Some(elf) = parser.finish() {
+        f(elf);
+    }
+    Ok(())
+}
 
-    let elves = parse_elves(&input);
-    let biggest_elf = most_calories(&elves);
-    println!("[Day  1][Part 1] - {}", biggest_elf);
+/// Keeps the `n` elves with the most calories seen so far in a min-heap, so
+/// finding them never holds more than `n` elves.
+struct TopN<C> {
+    n: usize,
+    // the smallest total is on top, and of equal totals the latest elf
+    heap: BinaryHeap<Reverse<(C, Reverse<usize>)>>,
 }
 
-pub fn part2() {
-    let input =
-        std::fs::read_to_string("input/day1_part1.txt").expect("Need to provide puzzle input");
+impl<C: Calories> TopN<C> {
+    fn new(n: usize) -> TopN<C> {
+        TopN {
+            n,
+            heap: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    fn push(&mut self, index: usize, calories: C) {
+        self.heap.push(Reverse((calories, Reverse(index))));
+        if self.heap.len() > self.n {
+            self.heap.pop();
+        }
+    }
+
+    /// The chosen elves as `(index, calories)`, most calories first.
+    fn into_vec(self) -> Vec<(usize, C)> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((calories, Reverse(index)))| (index, calories))
+            .collect()
+    }
+}
 
-    let mut elves = parse_elves(&input);
-    let top_3 = top_three_calories(&mut elves);
-    println!("[Day  1][Part 2] - {}", top_3);
+/// The `n` elves carrying the most calories as `(index, calories)`, most
+/// first and earlier elves first on ties. There are fewer than `n` when there
+/// aren't that many elves.
+pub fn top_n<C: Calories>(elves: &[Elf<C>], n: usize) -> Vec<(usize, C)> {
+    let mut top = TopN::new(n);
+    for elf in elves {
+        top.push(elf.index, elf.calories.clone());
+    }
+    top.into_vec()
+}
+
+fn total<C: Calories>(top: &[(usize, C)]) -> Result<C> {
+    let mut total = C::zero();
+    for (_, calories) in top {
+        let Some(sum) = total.checked_add(calories) else {
+            bail!(
+                "Calories of the top {} elves overflow {}",
+                top.len(),
+                type_name::<C>()
+            );
+        };
+        total = sum;
+    }
+    Ok(total)
+}
+
+fn stream_top_n<C: Calories>(input: &mut dyn BufRead, mode: Mode, n: usize) -> Result<C> {
+    let mut top = TopN::new(n);
+    stream_elves(input, mode, |elf: Elf<C>| top.push(elf.index, elf.calories))?;
+    total(&top.into_vec())
+}
+
+/// What the elves are carrying, for `--stats`.
+#[derive(Debug, PartialEq)]
+pub struct CalorieStats {
+    pub elves: usize,
+    pub items: usize,
+    /// Mean calories per elf.
+    pub mean: f64,
+    /// Median calories per elf, halfway between the middle two for an even count.
+    pub median: f64,
+    /// How many elves carry each number of items.
+    pub item_counts: BTreeMap<usize, usize>,
+    /// Indices of the elves carrying the most items.
+    pub most_items: Vec<usize>,
+}
+
+impl CalorieStats {
+    /// `None` when there are no elves to describe.
+    pub fn new<C: Calories>(elves: &[Elf<C>]) -> Option<CalorieStats> {
+        if elves.is_empty() {
+            return None;
+        }
+
+        let mut calories: Vec<f64> = elves
+            .iter()
+            .map(|e| e.calories.to_f64().unwrap_or(f64::INFINITY))
+            .collect();
+        calories.sort_by(f64::total_cmp);
+        let middle = calories.len() / 2;
+        let median = if calories.len().is_multiple_of(2) {
+            (calories[middle - 1] + calories[middle]) / 2.0
+        } else {
+            calories[middle]
+        };
+        let total: f64 = calories.iter().sum();
+
+        let mut item_counts = BTreeMap::new();
+        for elf in elves {
+            *item_counts.entry(elf.items.len()).or_insert(0) += 1;
+        }
+        let most = elves.iter().map(|e| e.items.len()).max().unwrap_or(0);
+
+        Some(CalorieStats {
+            elves: elves.len(),
+            items: elves.iter().map(|e| e.items.len()).sum(),
+            mean: total / elves.len() as f64,
+            median,
+            item_counts,
+            most_items: elves
+                .iter()
+                .filter(|e| e.items.len() == most)
+                .map(|e| e.index)
+                .collect(),
+        })
+    }
+}
+
+impl Display for CalorieStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |n: usize| if n == 1 { "elf" } else { "elves" };
+
+        writeln!(f, "elves: {}, items: {}", self.elves, self.items)?;
+        writeln!(
+            f,
+            "calories per elf: mean {:.1}, median {:.1}",
+            self.mean, self.median
+        )?;
+        let counts: Vec<String> = self
+            .item_counts
+            .iter()
+            .map(|(&items, &elves)| format!("{} ({} {})", items, elves, plural(elves)))
+            .collect();
+        writeln!(f, "items per elf: {}", counts.join(", "))?;
+        let most: Vec<String> = self.most_items.iter().map(usize::to_string).collect();
+        write!(
+            f,
+            "most items: {}, carried by {} {}",
+            self.item_counts.keys().next_back().unwrap_or(&0),
+            plural(most.len()),
+            most.join(", ")
+        )
+    }
+}
+
+/// What to count calories in, picked with `--width`.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Width {
+    #[default]
+    U64,
+    U128,
+    /// `num::BigUint`, which can't overflow but is slower.
+    Big,
+}
+
+/// Day 1, counting calories in `C`.
+pub struct Day1<C> {
+    mode: Mode,
+    calories: PhantomData<C>,
+}
+
+impl<C> Day1<C> {
+    pub fn new(mode: Mode) -> Day1<C> {
+        Day1 {
+            mode,
+            calories: PhantomData,
+        }
+    }
+}
+
+impl<C: Calories> Solution for Day1<C> {
+    type Input = (Vec<Elf<C>>, Normalized);
+    type Part1 = Answer;
+    type Part2 = Answer;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Ok(parse_elves(input, self.mode)?)
+    }
+
+    fn part1(&self, (elves, _): &Self::Input) -> Result<Self::Part1> {
+        Ok(Answer::number(total(&top_n(elves, 1))?))
+    }
+
+    fn part2(&self, (elves, _): &Self::Input) -> Result<Self::Part2> {
+        Ok(Answer::number(total(&top_n(elves, 3))?))
+    }
+
+    fn stats(&self, (elves, _): &Self::Input) -> Option<String> {
+        CalorieStats::new(elves).map(|stats| stats.to_string())
+    }
+
+    fn notes(&self, (_, normalized): &Self::Input) -> Vec<String> {
+        if normalized.is_empty() {
+            vec![]
+        } else {
+            vec![normalized.to_string()]
+        }
+    }
+}
+
+impl<C: Calories> Streaming for Day1<C> {
+    type Part1 = Answer;
+    type Part2 = Answer;
+
+    fn stream_part1(&self, input: &mut dyn BufRead) -> Result<Self::Part1> {
+        Ok(Answer::number(stream_top_n::<C>(input, self.mode, 1)?))
+    }
+
+    fn stream_part2(&self, input: &mut dyn BufRead) -> Result<Self::Part2> {
+        Ok(Answer::number(stream_top_n::<C>(input, self.mode, 3)?))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use num::BigUint;
+
+    use crate::generate::{self, Rng};
+    use crate::solution::{Answer, Runner, StreamRunner};
+
+    const EXAMPLE: &str = include_str!("../examples/day1/example.txt");
+
+    fn elf(index: usize, items: Vec<u32>) -> Elf<u32> {
+        Elf {
+            index,
+            calories: items.iter().sum(),
+            items,
+        }
+    }
 
     #[test]
     fn parse() {
-        let input = "1000
-2000
-3000
+        let input = EXAMPLE;
 
-4000
+        let elves = parse_elves::<u32>(input, Mode::Strict).unwrap().0;
+        assert_eq!(
+            vec![
+                elf(0, vec![1000, 2000, 3000]),
+                elf(1, vec![4000]),
+                elf(2, vec![5000, 6000]),
+                elf(3, vec![7000, 8000, 9000]),
+                elf(4, vec![10000])
+            ],
+            elves
+        )
+    }
+
+    #[test]
+    fn part1() {
+        let input = EXAMPLE;
 
-5000
-6000
+        let elves = parse_elves::<u32>(input, Mode::Strict).unwrap().0;
+        let top = top_n(&elves, 1);
+        assert_eq!(vec![(3, 24000)], top);
+        assert_eq!(24000, total(&top).unwrap());
+    }
 
-7000
-8000
-9000
+    #[test]
+    fn part2() {
+        let input = EXAMPLE;
+
+        let elves = parse_elves::<u32>(input, Mode::Strict).unwrap().0;
+        let top = top_n(&elves, 3);
+        assert_eq!(vec![(3, 24000), (2, 11000), (4, 10000)], top);
+        assert_eq!(45000, total(&top).unwrap());
+    }
 
-10000";
+    #[test]
+    fn stats_test() {
+        let stats =
+            CalorieStats::new(&parse_elves::<u32>(EXAMPLE, Mode::Strict).unwrap().0).unwrap();
+        assert_eq!(
+            CalorieStats {
+                elves: 5,
+                items: 10,
+                mean: 11000.0,
+                median: 10000.0,
+                item_counts: BTreeMap::from([(1, 2), (2, 1), (3, 2)]),
+                most_items: vec![0, 3],
+            },
+            stats
+        );
+        assert_eq!(
+            "elves: 5, items: 10
+calories per elf: mean 11000.0, median 10000.0
+items per elf: 1 (2 elves), 2 (1 elf), 3 (2 elves)
+most items: 3, carried by elves 0, 3",
+            stats.to_string()
+        );
+
+        let stats = CalorieStats::new(
+            &parse_elves::<u32>("1\n\n4\n5\n\n2", Mode::Strict)
+                .unwrap()
+                .0,
+        )
+        .unwrap();
+        assert_eq!(2.0, stats.median);
+        assert_eq!(vec![1], stats.most_items);
+        let stats = CalorieStats::new(
+            &parse_elves::<u32>("1\n\n4\n\n2\n\n8", Mode::Strict)
+                .unwrap()
+                .0,
+        )
+        .unwrap();
+        assert_eq!(3.0, stats.median);
+
+        assert_eq!(None, CalorieStats::new::<u32>(&[]));
+    }
+
+    #[test]
+    fn top_n_test() {
+        let elves = parse_elves::<u32>(EXAMPLE, Mode::Strict).unwrap().0;
+        assert_eq!(Vec::<(usize, u32)>::new(), top_n(&elves, 0));
+        assert_eq!(5, top_n(&elves, 10).len());
+        assert_eq!(Vec::<(usize, u32)>::new(), top_n(&[], 3));
+
+        // fewer elves than asked for
+        let elves = parse_elves::<u32>("5\n\n7", Mode::Strict).unwrap().0;
+        assert_eq!(12, total(&top_n(&elves, 3)).unwrap());
+
+        let tied = parse_elves::<u32>("5\n\n9\n\n5\n\n5", Mode::Strict)
+            .unwrap()
+            .0;
+        assert_eq!(vec![(1, 9), (0, 5), (2, 5)], top_n(&tied, 3));
+    }
+
+    #[test]
+    fn top_n_matches_sorting() {
+        let input = generate::calorie_lines(Rng::new(17), 500, 4)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let elves = parse_elves::<u32>(&input, Mode::Strict).unwrap().0;
+
+        let mut sorted: Vec<(usize, u32)> = elves.iter().map(|e| e.calories).enumerate().collect();
+        sorted.sort_by_key(|&(index, calories)| (Reverse(calories), index));
+        for n in [1, 3, 10, 499, 500, 501] {
+            assert_eq!(&sorted[..n.min(500)], top_n(&elves, n), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn streaming_matches_parsed() {
+        let lines = || generate::calorie_lines(Rng::new(11), 2000, 12);
+        let input = lines().collect::<Vec<_>>().join("\n");
+        assert_eq!(
+            Day1::<u32>::new(Mode::Lenient)
+                .run(&input, None)
+                .unwrap()
+                .answers(),
+            generate::stream_answers(&Day1::<u32>::new(Mode::Lenient), lines)
+        );
+        assert_eq!(
+            Day1::<u32>::new(Mode::Lenient)
+                .run(EXAMPLE, None)
+                .unwrap()
+                .answers(),
+            generate::stream_answers(&Day1::<u32>::new(Mode::Lenient), || EXAMPLE
+                .lines()
+                .map(str::to_owned))
+        );
+    }
 
-        let elves = parse_elves(input);
+    #[test]
+    fn streaming_keeps_no_items() {
+        let mut elves = vec![];
+        stream_elves(&mut "1\n2\n\n\n3".as_bytes(), Mode::Lenient, |elf| {
+            elves.push(elf)
+        })
+        .unwrap();
         assert_eq!(
             vec![
-                Elf { calories: 6000 },
-                Elf { calories: 4000 },
-                Elf { calories: 11000 },
-                Elf { calories: 24000 },
-                Elf { calories: 10000 }
+                Elf {
+                    index: 0,
+                    items: vec![],
+                    calories: 3u32,
+                },
+                Elf {
+                    index: 1,
+                    items: vec![],
+                    calories: 3,
+                },
             ],
             elves
-        )
+        );
     }
 
     #[test]
-    fn part1() {
-        let input = "1000
-2000
-3000
+    fn streams_large_input() {
+        let answers = generate::stream_answers(&Day1::<u32>::new(Mode::Lenient), || {
+            generate::calorie_lines(Rng::new(3), 200_000, 9)
+        });
+        let [(_, Answer::Number(most)), (_, Answer::Number(top_three))] = answers[..] else {
+            panic!("{:?}", answers);
+        };
+        assert!(most <= 9 * 9999 && top_three <= 3 * most);
+    }
 
-4000
+    #[test]
+    fn strict_rejects_sloppy_input() {
+        let err = |input| parse_elves::<u32>(input, Mode::Strict).unwrap_err();
 
-5000
-6000
+        let e = err("1000\r\n2000\r\n");
+        assert_eq!((1, 5), (e.line, e.column));
+        assert_eq!("line 1, column 5: Windows line ending", e.to_string());
 
-7000
-8000
-9000
+        let e = err("1000\n2000  \n");
+        assert_eq!((2, 5, "  "), (e.line, e.column, e.text.as_str()));
+        assert_eq!("Trailing whitespace", e.message);
 
-10000";
+        assert_eq!(3, err("1000\n\n\n2000").line);
+        assert_eq!(1, err("\n1000").line);
+        assert_eq!(1, err(" 1000").line);
 
-        let elves = parse_elves(input);
-        let max_calories = most_calories(&elves);
-        assert_eq!(24000, max_calories);
+        assert!(parse_elves::<u32>("1000\n\n2000\n", Mode::Strict).is_ok());
     }
 
     #[test]
-    fn part2() {
-        let input = "1000
-2000
-3000
+    fn lenient_normalizes() {
+        let sloppy = EXAMPLE.replace('\n', "\r\n").replace("4000", " 4000\t") + "\r\n\n";
+        let (elves, normalized) = parse_elves::<u32>(&sloppy, Mode::Lenient).unwrap();
+        assert_eq!(parse_elves::<u32>(EXAMPLE, Mode::Strict).unwrap().0, elves);
+        assert_eq!(
+            Normalized {
+                crlf: 15,
+                trimmed: 1,
+                extra_blank: 1,
+            },
+            normalized
+        );
+        assert_eq!(
+            "normalized 15 CRLF line endings, 1 line with extra whitespace, 1 extra blank line",
+            normalized.to_string()
+        );
 
-4000
+        let (_, normalized) = parse_elves::<u32>(EXAMPLE, Mode::Lenient).unwrap();
+        assert!(normalized.is_empty());
+        assert!(parse_elves::<u32>("1000\n10 00", Mode::Lenient).is_err());
+    }
 
-5000
-6000
+    #[test]
+    fn lenient_notes() {
+        let day1 = Day1::<u32>::new(Mode::Lenient);
+        let run = day1.run("1\r\n2\r\n\r\n3\r\n", None).unwrap();
+        assert_eq!(
+            vec![(1, Answer::Number(3)), (2, Answer::Number(6))],
+            run.answers()
+        );
+        assert_eq!(vec!["normalized 4 CRLF line endings"], run.notes);
+        assert!(day1.run(EXAMPLE, None).unwrap().notes.is_empty());
 
-7000
-8000
-9000
+        let strict = Day1::<u32>::new(Mode::Strict);
+        assert!(strict.run("1\r\n2\r\n", None).is_err());
+        let mut open = || -> Result<Box<dyn BufRead>> { Ok(Box::new("1\r\n2\r\n".as_bytes())) };
+        assert!(strict.stream(&mut open, Some(1)).is_err());
+        assert_eq!(
+            vec![(1, Answer::Number(3))],
+            day1.stream(&mut open, Some(1)).unwrap().answers()
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let err = parse_elves::<u32>("4294967295\n1", Mode::Strict).unwrap_err();
+        assert_eq!(
+            "line 2, column 1: Elf's calories overflow u32 `1`",
+            err.to_string()
+        );
+        let err = parse_elves::<u32>("4294967296", Mode::Strict).unwrap_err();
+        assert_eq!("Calorie count too big for u32", err.message);
+
+        let (elves, _) = parse_elves::<u64>("4294967295\n1", Mode::Strict).unwrap();
+        assert_eq!(4294967296, elves[0].calories);
+
+        // every elf fits, but not the top three together
+        let input = "4000000000\n\n4000000000\n\n4000000000";
+        let day1 = Day1::<u32>::new(Mode::Strict);
+        let parsed = day1.parse(input).unwrap();
+        assert_eq!(Answer::Number(4000000000), day1.part1(&parsed).unwrap());
+        let err = day1.part2(&parsed).unwrap_err();
+        assert_eq!("Calories of the top 3 elves overflow u32", err.to_string());
+        assert!(Day1::<u32>::new(Mode::Lenient).run(input, None).is_err());
+        assert_eq!(
+            vec![
+                (1, Answer::Number(4000000000)),
+                (2, Answer::Number(12000000000))
+            ],
+            Day1::<u64>::new(Mode::Lenient)
+                .run(input, None)
+                .unwrap()
+                .answers()
+        );
+    }
+
+    #[test]
+    fn wider_accumulators() {
+        let input = "18446744073709551615\n1\n\n5";
+        assert!(Day1::<u64>::new(Mode::Strict).run(input, None).is_err());
+        let expected = vec![
+            (1, Answer::number("18446744073709551616")),
+            (2, Answer::number("18446744073709551621")),
+        ];
+        assert_eq!(
+            expected,
+            Day1::<u128>::new(Mode::Strict)
+                .run(input, None)
+                .unwrap()
+                .answers()
+        );
+        assert_eq!(
+            expected,
+            Day1::<BigUint>::new(Mode::Strict)
+                .run(input, None)
+                .unwrap()
+                .answers()
+        );
+
+        let huge = "1".repeat(60);
+        let run = Day1::<BigUint>::new(Mode::Strict).run(&format!("{0}\n{0}", huge), Some(1));
+        assert_eq!(
+            vec![(1, Answer::number("2".repeat(60)))],
+            run.unwrap().answers()
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        let input = "1000
+2000
 
-10000";
+30O0";
 
-        let mut elves = parse_elves(input);
-        let max_calories = top_three_calories(&mut elves);
-        assert_eq!(45000, max_calories);
+        let err = parse_elves::<u32>(input, Mode::Strict).unwrap_err();
+        assert_eq!(4, err.line);
+        assert_eq!(1, err.column);
+        assert_eq!("30O0", err.text);
     }
 }
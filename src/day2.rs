@@ -1,163 +1,575 @@
-#[derive(PartialEq, Debug, Copy, Clone)]
-enum Move {
-    Rock,
-    Paper,
-    Scissors,
-}
-
-impl Move {
-    pub fn score(&self) -> u32 {
-        match self {
-            Move::Rock => 1,
-            Move::Paper => 2,
-            Move::Scissors => 3,
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+
+use crate::analysis::{self, Distribution};
+use crate::game::{Game, Move, Outcome};
+use crate::parse::{parse_lines, ParseError};
+use crate::solution::Solution;
+
+pub type Round = (Move, Move);
+
+/// What the letters of a strategy guide stand for. The opponent's column is
+/// always a move, ours is a move in part 1 and the outcome to aim for in
+/// part 2.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Symbols {
+    opponent: BTreeMap<String, Move>,
+    response: BTreeMap<String, Move>,
+    outcomes: BTreeMap<String, Outcome>,
+}
+
+impl Default for Symbols {
+    fn default() -> Self {
+        let moves = |letters: [&str; 3]| {
+            letters
+                .into_iter()
+                .zip(Game::rock_paper_scissors().moves())
+                .map(|(letter, mov)| (letter.to_owned(), mov))
+                .collect()
+        };
+        Symbols {
+            opponent: moves(["A", "B", "C"]),
+            response: moves(["X", "Y", "Z"]),
+            outcomes: ["X", "Y", "Z"]
+                .into_iter()
+                .zip([Outcome::Lose, Outcome::Draw, Outcome::Win])
+                .map(|(letter, outcome)| (letter.to_owned(), outcome))
+                .collect(),
         }
     }
 }
 
-type Round = (Move, Move);
+const OUTCOMES: [(&str, Outcome); 3] = [
+    ("lose", Outcome::Lose),
+    ("draw", Outcome::Draw),
+    ("win", Outcome::Win),
+];
 
-fn move_beats_move(m1: Move, m2: Move) -> bool {
-    match m1 {
-        Move::Rock => m2 == Move::Scissors,
-        Move::Paper => m2 == Move::Rock,
-        Move::Scissors => m2 == Move::Paper,
-    }
+fn outcome_name(outcome: Outcome) -> &'static str {
+    OUTCOMES.iter().find(|(_, o)| *o == outcome).unwrap().0
 }
 
-fn score_round(round: Round) -> u32 {
-    round.1.score()
-        + if move_beats_move(round.1, round.0) {
-            6
-        } else if round.0 == round.1 {
-            3
-        } else {
-            0
+impl Symbols {
+    /// Reads symbols for the moves of `game` from a small TOML file. Tables
+    /// that are left out keep the usual letters.
+    ///
+    /// ```toml
+    /// [opponent]
+    /// A = "rock"
+    /// B = "paper"
+    /// C = "scissors"
+    ///
+    /// [response]
+    /// X = "paper"
+    /// Y = "scissors"
+    /// Z = "rock"
+    ///
+    /// [outcome]
+    /// X = "lose"
+    /// Y = "draw"
+    /// Z = "win"
+    /// ```
+    pub fn parse(input: &str, game: &Game) -> Result<Symbols> {
+        let mut symbols = Symbols::default();
+        let mut table: Option<String> = None;
+        let mut seen = vec![];
+
+        for (i, line) in input.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let name = name.trim();
+                match name {
+                    "opponent" => symbols.opponent.clear(),
+                    "response" => symbols.response.clear(),
+                    "outcome" => symbols.outcomes.clear(),
+                    _ => bail!(
+                        "Line {}: unknown table `[{}]`, expected opponent, response or outcome",
+                        line_no,
+                        name
+                    ),
+                }
+                if seen.contains(&name) {
+                    bail!("Line {}: table `[{}]` appears twice", line_no, name);
+                }
+                seen.push(name);
+                table = Some(name.to_owned());
+                continue;
+            }
+
+            let (symbol, value) = line
+                .split_once('=')
+                .with_context(|| format!("Line {}: expected `SYMBOL = value`", line_no))?;
+            let symbol = symbol.trim();
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            if symbol.is_empty() || symbol.contains(char::is_whitespace) {
+                bail!("Line {}: invalid symbol `{}`", line_no, symbol);
+            }
+
+            let duplicate = match table.as_deref() {
+                None => bail!("Line {}: symbol outside of a table", line_no),
+                Some("outcome") => {
+                    let outcome = OUTCOMES
+                        .iter()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(value))
+                        .with_context(|| {
+                            format!(
+                                "Line {}: unknown outcome `{}`, expected lose, draw or win",
+                                line_no, value
+                            )
+                        })?
+                        .1;
+                    symbols
+                        .outcomes
+                        .insert(symbol.to_owned(), outcome)
+                        .is_some()
+                }
+                Some(table) => {
+                    let mov = game
+                        .find(value)
+                        .with_context(|| format!("Line {}: unknown move `{}`", line_no, value))?;
+                    let moves = match table {
+                        "opponent" => &mut symbols.opponent,
+                        _ => &mut symbols.response,
+                    };
+                    moves.insert(symbol.to_owned(), mov).is_some()
+                }
+            };
+            if duplicate {
+                bail!("Line {}: `{}` is already defined", line_no, symbol);
+            }
         }
-}
 
-fn parse_round(input: &str) -> Round {
-    let mut moves = input.split_ascii_whitespace().take(2);
-    let first = moves.next().expect("Should be a valid move");
-    let second = moves.next().expect("Should be a valid move");
+        for (name, empty) in [
+            ("opponent", symbols.opponent.is_empty()),
+            ("response", symbols.response.is_empty()),
+            ("outcome", symbols.outcomes.is_empty()),
+        ] {
+            if empty {
+                bail!("Table `[{}]` has no symbols", name);
+            }
+        }
+
+        Ok(symbols)
+    }
 
-    let first = parse_move(first);
-    let second = parse_move(second);
+    pub fn load(path: &Path) -> Result<Symbols> {
+        let input = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read symbols from {}", path.display()))?;
+        Symbols::parse(&input, &Game::rock_paper_scissors())
+            .with_context(|| format!("Invalid symbols file {}", path.display()))
+    }
 
-    (first, second)
+    /// The symbols in the format [`Symbols::parse`] reads.
+    pub fn format(&self, game: &Game) -> String {
+        let mut out = String::new();
+        for (table, moves) in [("opponent", &self.opponent), ("response", &self.response)] {
+            writeln!(out, "[{}]", table).unwrap();
+            for (symbol, &mov) in moves {
+                writeln!(out, "{} = \"{}\"", symbol, game.name(mov)).unwrap();
+            }
+            out.push('\n');
+        }
+        out.push_str("[outcome]\n");
+        for (symbol, &outcome) in &self.outcomes {
+            writeln!(out, "{} = \"{}\"", symbol, outcome_name(outcome)).unwrap();
+        }
+        out
+    }
 }
 
-fn parse_move(input: &str) -> Move {
-    match input {
-        "A" => Move::Rock,
-        "B" => Move::Paper,
-        "C" => Move::Scissors,
-        "X" => Move::Rock,
-        "Y" => Move::Paper,
-        "Z" => Move::Scissors,
-        _ => panic!("Invalid move"),
+fn split_columns(line: &str) -> Result<(&str, &str), ParseError> {
+    let mut columns = line.split_ascii_whitespace();
+    let first = columns
+        .next()
+        .ok_or_else(|| ParseError::missing(line, "Missing move"))?;
+    let second = columns
+        .next()
+        .ok_or_else(|| ParseError::missing(line, "Missing second column"))?;
+    if let Some(extra) = columns.next() {
+        return Err(ParseError::new(line, extra, "Unexpected text"));
     }
+
+    Ok((first, second))
+}
+
+fn parse_round(line: &str, symbols: &Symbols) -> Result<Round, ParseError> {
+    let (first, second) = split_columns(line)?;
+
+    let first = parse_move(line, first, &symbols.opponent)?;
+    let second = parse_move(line, second, &symbols.response)?;
+
+    Ok((first, second))
+}
+
+fn parse_move(line: &str, input: &str, moves: &BTreeMap<String, Move>) -> Result<Move, ParseError> {
+    moves
+        .get(input)
+        .copied()
+        .ok_or_else(|| ParseError::new(line, input, "Invalid move"))
 }
 
-#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
-enum Strategy {
-    Lose,
-    Draw,
-    Win,
+fn parse_strategy(
+    line: &str,
+    input: &str,
+    outcomes: &BTreeMap<String, Outcome>,
+) -> Result<Outcome, ParseError> {
+    outcomes
+        .get(input)
+        .copied()
+        .ok_or_else(|| ParseError::new(line, input, "Invalid strategy"))
+}
+
+fn parse_part_two(line: &str, symbols: &Symbols) -> Result<(Move, Outcome), ParseError> {
+    let (first, second) = split_columns(line)?;
+
+    let first = parse_move(line, first, &symbols.opponent)?;
+    let second = parse_strategy(line, second, &symbols.outcomes)?;
+
+    Ok((first, second))
 }
 
-fn parse_strategy(input: &str) -> Strategy {
-    match input {
-        "X" => Strategy::Lose,
-        "Y" => Strategy::Draw,
-        "Z" => Strategy::Win,
-        _ => panic!("Invalid strategy"),
+/// Both columns of `guide` read as moves.
+pub fn parse_guide(guide: &str, symbols: &Symbols) -> Result<Vec<Round>> {
+    let rounds = parse_lines(guide, |line| parse_round(line, symbols))?;
+    if rounds.is_empty() {
+        bail!("The guide has no rounds");
     }
+    Ok(rounds)
 }
 
-fn fit_strategy(mov: Move, strat: Strategy) -> Move {
-    match mov {
-        Move::Rock => match strat {
-            Strategy::Lose => Move::Scissors,
-            Strategy::Draw => Move::Rock,
-            Strategy::Win => Move::Paper,
-        },
-        Move::Paper => match strat {
-            Strategy::Lose => Move::Rock,
-            Strategy::Draw => Move::Paper,
-            Strategy::Win => Move::Scissors,
-        },
-        Move::Scissors => match strat {
-            Strategy::Lose => Move::Paper,
-            Strategy::Draw => Move::Scissors,
-            Strategy::Win => Move::Rock,
-        },
+/// Every way of reading our column of `guide` as different moves that scores
+/// `target` in part 1, keeping the opponent's column as `symbols` has it.
+pub fn find_symbols(guide: &str, symbols: &Symbols, target: u32) -> Result<Vec<Symbols>> {
+    let game = Game::rock_paper_scissors();
+    let letters: Vec<&String> = symbols.response.keys().collect();
+
+    // Guides repeat the same few rounds, so each mapping only scores those.
+    let mut counts: HashMap<(Move, usize), u32> = HashMap::new();
+    for (opponent, letter) in parse_lines(guide, |line| {
+        let (first, second) = split_columns(line)?;
+        let letter = letters
+            .iter()
+            .position(|&letter| letter == second)
+            .ok_or_else(|| ParseError::new(line, second, "Invalid move"))?;
+        Ok((parse_move(line, first, &symbols.opponent)?, letter))
+    })? {
+        *counts.entry((opponent, letter)).or_default() += 1;
     }
+
+    Ok(game
+        .moves()
+        .permutations(letters.len())
+        .filter(|moves| {
+            let score: u32 = counts
+                .iter()
+                .map(|(&(opponent, letter), &count)| {
+                    count * game.score_round((opponent, moves[letter]))
+                })
+                .sum();
+            score == target
+        })
+        .map(|moves| Symbols {
+            response: letters.iter().map(|&l| l.clone()).zip(moves).collect(),
+            ..symbols.clone()
+        })
+        .collect())
 }
 
-fn parse_part_two(input: &str) -> (Move, Strategy) {
-    let mut moves = input.split_ascii_whitespace().take(2);
-    let first = moves.next().expect("Should be a valid move");
-    let second = moves.next().expect("Should be a valid strategy");
+/// How one line of a guide was scored.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Step {
+    pub line: usize,
+    pub theirs: Move,
+    /// The outcome the line asks for, in part 2.
+    pub strategy: Option<Outcome>,
+    /// Our move, read from the guide in part 1 and fitted to the strategy in
+    /// part 2.
+    pub mine: Move,
+    pub outcome: Outcome,
+    pub shape_score: u32,
+    pub outcome_score: u32,
+    /// The score of this line and every line before it.
+    pub total: u32,
+}
 
-    let first = parse_move(first);
-    let second = parse_strategy(second);
+/// Scores `guide` a line at a time the way `part` does.
+pub fn explain(game: &Game, guide: &str, symbols: &Symbols, part: u32) -> Result<Vec<Step>> {
+    let rounds: Vec<(Move, Option<Outcome>, Move)> = if part == 1 {
+        parse_lines(guide, |line| parse_round(line, symbols))?
+            .into_iter()
+            .map(|(theirs, mine)| (theirs, None, mine))
+            .collect()
+    } else {
+        parse_lines(guide, |line| parse_part_two(line, symbols))?
+            .into_iter()
+            .map(|(theirs, s)| (theirs, Some(s), game.fit_strategy(theirs, s)))
+            .collect()
+    };
+
+    let mut total = 0;
+    Ok(rounds
+        .into_iter()
+        .enumerate()
+        .map(|(i, (theirs, strategy, mine))| {
+            let outcome = game.outcome(mine, theirs);
+            let shape_score = game.move_score(mine);
+            let outcome_score = game.outcome_score(outcome);
+            total += shape_score + outcome_score;
+            Step {
+                line: i + 1,
+                theirs,
+                strategy,
+                mine,
+                outcome,
+                shape_score,
+                outcome_score,
+                total,
+            }
+        })
+        .collect())
+}
 
-    (first, second)
+/// The text of every column of `step`, in the order of [`EXPLAIN_COLUMNS`].
+fn step_columns(game: &Game, step: &Step) -> [String; 8] {
+    [
+        step.line.to_string(),
+        game.name(step.theirs).to_owned(),
+        step.strategy.map_or("", outcome_name).to_owned(),
+        game.name(step.mine).to_owned(),
+        step.shape_score.to_string(),
+        outcome_name(step.outcome).to_owned(),
+        step.outcome_score.to_string(),
+        step.total.to_string(),
+    ]
 }
 
-fn part1(input: &str) {
-    let score: u32 = input.lines().map(parse_round).map(score_round).sum();
+const EXPLAIN_COLUMNS: [&str; 8] = [
+    "line", "opponent", "strategy", "response", "shape", "outcome", "points", "total",
+];
+
+/// `steps` as a table, numbers aligned right and names left.
+pub fn explain_table(game: &Game, steps: &[Step]) -> String {
+    let rows: Vec<[String; 8]> = steps.iter().map(|step| step_columns(game, step)).collect();
+    let widths: Vec<usize> = (0..8)
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain([EXPLAIN_COLUMNS[i].len()])
+                .max()
+                .unwrap()
+        })
+        .collect();
+    // opponent, strategy, response and outcome hold names
+    let names = [1, 2, 3, 5];
+    let line = |cells: Vec<&str>| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &width))| {
+                if names.contains(&i) {
+                    format!("{:<width$}", cell)
+                } else {
+                    format!("{:>width$}", cell)
+                }
+            })
+            .collect();
+        cells.join("  ").trim_end().to_owned()
+    };
+
+    let mut lines = vec![line(EXPLAIN_COLUMNS.to_vec())];
+    for row in &rows {
+        lines.push(line(row.iter().map(String::as_str).collect()));
+    }
+    lines.join("\n")
+}
 
-    println!("[Day  2][Part 1] - {}", score)
+/// The steps of every part as CSV, with the part in the first column.
+pub fn explain_csv(game: &Game, parts: &[(u32, Vec<Step>)]) -> String {
+    let mut out = format!("part,{}\n", EXPLAIN_COLUMNS.join(","));
+    for (part, steps) in parts {
+        for step in steps {
+            writeln!(out, "{},{}", part, step_columns(game, step).join(",")).unwrap();
+        }
+    }
+    out
 }
 
-fn part2(input: &str) {
-    let score: u32 = input
-        .lines()
-        .map(parse_part_two)
-        .map(|(m, s)| (m, fit_strategy(m, s)))
-        .map(score_round)
-        .sum();
+pub struct Day2 {
+    game: Game,
+    symbols: Symbols,
+}
 
-    println!("[Day  2][Part 2] - {}", score);
+impl Day2 {
+    pub fn new(symbols: Symbols) -> Day2 {
+        Day2 {
+            game: Game::rock_paper_scissors(),
+            symbols,
+        }
+    }
+}
+
+impl Default for Day2 {
+    fn default() -> Self {
+        Day2::new(Symbols::default())
+    }
 }
 
-pub fn solve() {
-    let input = std::fs::read_to_string("input/day2.txt").expect("Need input");
+/// The guide read the way each part reads it. With symbols of their own, the
+/// letters of a guide may only mean something to one of the parts, so each
+/// part only fails on its own reading.
+type Readings = (
+    Result<Vec<Round>, ParseError>,
+    Result<Vec<(Move, Outcome)>, ParseError>,
+);
+
+impl Solution for Day2 {
+    type Input = Readings;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        let rounds = parse_lines(input, |line| parse_round(line, &self.symbols));
+        let strategies = parse_lines(input, |line| parse_part_two(line, &self.symbols));
+        if let (Err(err), Err(_)) = (&rounds, &strategies) {
+            return Err(err.clone().into());
+        }
+        Ok((rounds, strategies))
+    }
+
+    fn part1(&self, (rounds, _): &Self::Input) -> Result<Self::Part1> {
+        let rounds = rounds.as_ref().map_err(Clone::clone)?;
+        Ok(rounds
+            .iter()
+            .map(|&round| self.game.score_round(round))
+            .sum())
+    }
 
-    part1(&input);
-    part2(&input);
+    fn part2(&self, (_, strategies): &Self::Input) -> Result<Self::Part2> {
+        let strategies = strategies.as_ref().map_err(Clone::clone)?;
+        let game = &self.game;
+        Ok(strategies
+            .iter()
+            .map(|&(m, s)| game.score_round((m, game.fit_strategy(m, s))))
+            .sum())
+    }
+
+    fn stats(&self, (rounds, strategies): &Self::Input) -> Option<String> {
+        let opponent: Vec<Move> = match (rounds, strategies) {
+            (Ok(rounds), _) => rounds.iter().map(|&(theirs, _)| theirs).collect(),
+            (_, Ok(strategies)) => strategies.iter().map(|&(theirs, _)| theirs).collect(),
+            _ => return None,
+        };
+        if opponent.is_empty() {
+            return None;
+        }
+        Some(stats(&self.game, &opponent, rounds.as_ref().ok()))
+    }
+}
+
+/// How the guide's opponent could be played against, and how the guide's
+/// own moves compare when there are any.
+fn stats(game: &Game, opponent: &[Move], rounds: Option<&Vec<Round>>) -> String {
+    let mut out = String::new();
+    writeln!(out, "rounds: {}", opponent.len()).unwrap();
+    writeln!(
+        out,
+        "best score: {}, worst score: {}",
+        analysis::best_score(game, opponent),
+        analysis::worst_score(game, opponent)
+    )
+    .unwrap();
+
+    let random = Distribution::random_moves(game, opponent);
+    writeln!(
+        out,
+        "random moves: mean {:.1}, std dev {:.1}",
+        random.mean(),
+        random.std_dev()
+    )
+    .unwrap();
+    if let Some(rounds) = rounds {
+        let score: u32 = rounds.iter().map(|&round| game.score_round(round)).sum();
+        writeln!(
+            out,
+            "the guide's moves score {}, beating {:.1}% of random moves",
+            score,
+            random.below(score) * 100.0
+        )
+        .unwrap();
+    }
+
+    let counts: Vec<f64> = game
+        .moves()
+        .map(|mov| opponent.iter().filter(|&&theirs| theirs == mov).count() as f64)
+        .collect();
+    let profile: Vec<String> = game
+        .moves()
+        .zip(&counts)
+        .map(|(mov, count)| {
+            format!(
+                "{} {:.1}%",
+                game.name(mov),
+                count * 100.0 / opponent.len() as f64
+            )
+        })
+        .collect();
+    writeln!(out, "opponent plays: {}", profile.join(", ")).unwrap();
+    let (best, expected) = analysis::best_response(game, &counts).unwrap();
+    write!(
+        out,
+        "best single move: {}, {:.2} a round",
+        game.name(best),
+        expected
+    )
+    .unwrap();
+    out
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solution::{Answer, Runner};
+
+    const ROCK: Move = Move(0);
+    const PAPER: Move = Move(1);
+    const SCISSORS: Move = Move(2);
+
+    const EXAMPLE: &str = include_str!("../examples/day2/example.txt");
 
     #[test]
     fn part1() {
-        let input = "A Y
-B X
-C Z";
+        let input = EXAMPLE;
 
-        let rounds: Vec<Round> = input.lines().map(parse_round).collect();
+        let rounds: Vec<Round> =
+            parse_lines(input, |line| parse_round(line, &Symbols::default())).unwrap();
 
         assert_eq!(
-            vec![
-                (Move::Rock, Move::Paper),
-                (Move::Paper, Move::Rock),
-                (Move::Scissors, Move::Scissors)
-            ],
+            vec![(ROCK, PAPER), (PAPER, ROCK), (SCISSORS, SCISSORS)],
             rounds
         );
 
-        let first = score_round(rounds[0]);
+        let game = Game::rock_paper_scissors();
+        let first = game.score_round(rounds[0]);
         assert_eq!(8, first);
 
-        let second = score_round(rounds[1]);
+        let second = game.score_round(rounds[1]);
         assert_eq!(1, second);
 
-        let third = score_round(rounds[2]);
+        let third = game.score_round(rounds[2]);
         assert_eq!(6, third);
 
         assert_eq!(15, first + second + third);
@@ -165,16 +577,15 @@ C Z";
 
     #[test]
     fn parse_part2() {
-        let input = "A Y
-B X
-C Z";
+        let input = EXAMPLE;
 
-        let moves: Vec<(Move, Strategy)> = input.lines().map(parse_part_two).collect();
+        let moves: Vec<(Move, Outcome)> =
+            parse_lines(input, |line| parse_part_two(line, &Symbols::default())).unwrap();
         assert_eq!(
             vec![
-                (Move::Rock, Strategy::Draw),
-                (Move::Paper, Strategy::Lose),
-                (Move::Scissors, Strategy::Win)
+                (ROCK, Outcome::Draw),
+                (PAPER, Outcome::Lose),
+                (SCISSORS, Outcome::Win)
             ],
             moves
         );
@@ -182,16 +593,224 @@ C Z";
 
     #[test]
     fn part2() {
-        let input = "A Y
-B X
-C Z";
+        let input = EXAMPLE;
+        let game = Game::rock_paper_scissors();
         let score: u32 = input
             .lines()
-            .map(parse_part_two)
-            .map(|(m, s)| (m, fit_strategy(m, s)))
-            .map(score_round)
+            .map(|line| parse_part_two(line, &Symbols::default()).unwrap())
+            .map(|(m, s)| game.score_round((m, game.fit_strategy(m, s))))
             .sum();
 
         assert_eq!(12, score);
     }
+
+    #[test]
+    fn parse_errors() {
+        let symbols = Symbols::default();
+        let parse_round = |line| parse_round(line, &symbols);
+        let parse_part_two = |line| parse_part_two(line, &symbols);
+
+        let err = parse_lines("A Y\nB Q", parse_round).unwrap_err();
+        assert_eq!((2, 3, "Q"), (err.line, err.column, err.text.as_str()));
+
+        let err = parse_lines("A Y\nB", parse_part_two).unwrap_err();
+        assert_eq!(
+            (2, 2, "Missing second column"),
+            (err.line, err.column, err.message.as_str())
+        );
+
+        let err = parse_part_two("A A").unwrap_err();
+        assert_eq!("Invalid strategy", err.message);
+
+        let err = parse_round("A Y Z").unwrap_err();
+        assert_eq!((5, "Z"), (err.column, err.text.as_str()));
+    }
+
+    #[test]
+    fn symbols_test() {
+        let game = Game::rock_paper_scissors();
+        let symbols = Symbols::parse(
+            "# the elves swapped their letters around
+[response]
+X = \"paper\"
+Y = Scissors
+Z = rock
+",
+            &game,
+        )
+        .unwrap();
+        assert_eq!(Symbols::default().opponent, symbols.opponent);
+        assert_eq!(Symbols::default().outcomes, symbols.outcomes);
+        assert_eq!(Some(&PAPER), symbols.response.get("X"));
+        assert_eq!(Some(&ROCK), symbols.response.get("Z"));
+
+        assert_eq!(
+            symbols,
+            Symbols::parse(&symbols.format(&game), &game).unwrap()
+        );
+        assert_eq!(
+            Symbols::default(),
+            Symbols::parse(&Symbols::default().format(&game), &game).unwrap()
+        );
+    }
+
+    #[test]
+    fn other_letters() {
+        let symbols = Symbols::parse(
+            "[opponent]
+R = rock
+P = paper
+S = scissors
+
+[response]
+r = rock
+p = paper
+s = scissors
+
+[outcome]
+l = lose
+d = draw
+w = win
+",
+            &Game::rock_paper_scissors(),
+        )
+        .unwrap();
+
+        let day2 = Day2::new(symbols);
+        assert!(day2.run(EXAMPLE, None).is_err());
+
+        // each guide only makes sense to one of the parts
+        let moves = "R p\nS s\nP r";
+        let outcomes = "R d\nP l\nS w";
+        assert_eq!(
+            vec![(1, Answer::Number(15))],
+            day2.run(moves, Some(1)).unwrap().answers()
+        );
+        assert_eq!(
+            vec![(2, Answer::Number(12))],
+            day2.run(outcomes, Some(2)).unwrap().answers()
+        );
+        let err = day2.run(outcomes, None).unwrap_err();
+        assert_eq!("line 1, column 3: Invalid move `d`", format!("{:#}", err));
+    }
+
+    #[test]
+    fn symbols_errors() {
+        let game = Game::rock_paper_scissors();
+        let err = |input: &str| format!("{:#}", Symbols::parse(input, &game).unwrap_err());
+
+        assert_eq!("Line 1: symbol outside of a table", err("X = rock"));
+        assert_eq!(
+            "Line 2: unknown move `lizard`",
+            err("[response]\nX = lizard")
+        );
+        assert_eq!(
+            "Line 2: unknown outcome `tie`, expected lose, draw or win",
+            err("[outcome]\nY = tie")
+        );
+        assert_eq!(
+            "Line 3: `X` is already defined",
+            err("[response]\nX = rock\nX = paper")
+        );
+        assert_eq!(
+            "Line 3: table `[response]` appears twice",
+            err("[response]\nX = rock\n[response]")
+        );
+        assert_eq!("Table `[opponent]` has no symbols", err("[opponent]"));
+        assert!(Symbols::parse("[moves]", &game).is_err());
+        assert!(Symbols::parse("[response]\nX Y = rock", &game).is_err());
+    }
+
+    #[test]
+    fn find_symbols_test() {
+        let symbols = Symbols::default();
+        let found = find_symbols(EXAMPLE, &symbols, 15).unwrap();
+        assert!(found.contains(&symbols));
+
+        // every mapping that scores 15 gives 15 when solving with it
+        for symbols in &found {
+            let answers = Day2::new(symbols.clone()).run(EXAMPLE, Some(1)).unwrap();
+            assert_eq!(vec![(1, Answer::Number(15))], answers.answers());
+        }
+
+        // the scores of all six mappings
+        let mut scores: Vec<u32> = (0..=30)
+            .flat_map(|target| {
+                let found = find_symbols(EXAMPLE, &symbols, target).unwrap().len();
+                std::iter::repeat_n(target, found)
+            })
+            .collect();
+        scores.sort();
+        assert_eq!(6, scores.len());
+
+        assert!(find_symbols(EXAMPLE, &symbols, 1000).unwrap().is_empty());
+        assert!(find_symbols("A Q", &symbols, 1).is_err());
+    }
+
+    #[test]
+    fn stats_test() {
+        let day2 = Day2::default();
+        assert_eq!(
+            Some(
+                "rounds: 3
+best score: 24, worst score: 6
+random moves: mean 15.0, std dev 4.5
+the guide's moves score 15, beating 40.7% of random moves
+opponent plays: rock 33.3%, paper 33.3%, scissors 33.3%
+best single move: scissors, 6.00 a round"
+                    .to_owned()
+            ),
+            Runner::stats(&day2, EXAMPLE).unwrap()
+        );
+
+        // a guide only the second part can read still has an opponent
+        let symbols = Symbols::parse("[outcome]\nL = lose\nD = draw\nW = win", &day2.game).unwrap();
+        let day2 = Day2::new(symbols);
+        let stats = Runner::stats(&day2, "A L\nA W").unwrap().unwrap();
+        assert!(stats.contains("opponent plays: rock 100.0%, paper 0.0%, scissors 0.0%"));
+        assert!(stats.contains("best single move: paper, 8.00 a round"));
+        assert!(!stats.contains("the guide's moves"));
+    }
+
+    #[test]
+    fn explain_test() {
+        let game = Game::rock_paper_scissors();
+        let symbols = Symbols::default();
+        let part1 = explain(&game, EXAMPLE, &symbols, 1).unwrap();
+        let part2 = explain(&game, EXAMPLE, &symbols, 2).unwrap();
+        assert_eq!(15, part1.last().unwrap().total);
+        assert_eq!(12, part2.last().unwrap().total);
+        assert_eq!(
+            Step {
+                line: 3,
+                theirs: SCISSORS,
+                strategy: Some(Outcome::Win),
+                mine: ROCK,
+                outcome: Outcome::Win,
+                shape_score: 1,
+                outcome_score: 6,
+                total: 12,
+            },
+            part2[2]
+        );
+
+        assert_eq!(
+            "line  opponent  strategy  response  shape  outcome  points  total
+   1  rock                paper         2  win           6      8
+   2  paper               rock          1  lose          0      9
+   3  scissors            scissors      3  draw          3     15",
+            explain_table(&game, &part1)
+        );
+        assert_eq!(
+            "part,line,opponent,strategy,response,shape,outcome,points,total
+2,1,rock,draw,rock,1,draw,3,4
+2,2,paper,lose,rock,1,lose,0,5
+2,3,scissors,win,rock,1,win,6,12
+",
+            explain_csv(&game, &[(2, part2)])
+        );
+
+        let err = explain(&game, "A Y\nB Q", &symbols, 1).unwrap_err();
+        assert_eq!("line 2, column 3: Invalid move `Q`", format!("{:#}", err));
+    }
 }
@@ -1,44 +1,39 @@
-use std::str::FromStr;
+use anyhow::Result;
 
-#[derive(PartialEq, Debug, Copy, Clone)]
-enum Move {
-    Rock,
-    Paper,
-    Scissors,
-}
+use crate::parse::{parse_lines, ParseError};
+use crate::solution::Solution;
 
-impl Move {
-    pub fn score(&self) -> i32 {
-        match self {
-            Move::Rock => 1,
-            Move::Paper => 2,
-            Move::Scissors => 3,
-        }
+fn parse_move(line: &str, input: &str) -> Result<i32, ParseError> {
+    match input {
+        "A" => Ok(0),
+        "B" => Ok(1),
+        "C" => Ok(2),
+        "X" => Ok(0),
+        "Y" => Ok(1),
+        "Z" => Ok(2),
+        _ => Err(ParseError::new(line, input, "Invalid move")),
     }
 }
 
-const MOVES: [Move; 3] = [Move::Rock, Move::Paper, Move::Scissors];
-
-fn parse_move(input: &str) -> i32 {
-    match input {
-        "A" => 0,
-        "B" => 1,
-        "C" => 2,
-        "X" => 0,
-        "Y" => 1,
-        "Z" => 2,
-        _ => panic!("Invalid move"),
+fn parse_line(line: &str) -> Result<(i32, i32), ParseError> {
+    let mut moves = line.split_ascii_whitespace();
+    let first = moves
+        .next()
+        .ok_or_else(|| ParseError::missing(line, "Missing move"))?;
+    let second = moves
+        .next()
+        .ok_or_else(|| ParseError::missing(line, "Missing move"))?;
+    if let Some(extra) = moves.next() {
+        return Err(ParseError::new(line, extra, "Unexpected text"));
     }
-}
 
-fn part1(input: &str) -> i32 {
-    input
-        .lines()
-        .map(|line| {
-            let mut moves = line.split_ascii_whitespace().take(2).map(parse_move);
-            let first = moves.next().expect("Valid Move");
-            let second = moves.next().expect("Valid Move");
+    Ok((parse_move(line, first)?, parse_move(line, second)?))
+}
 
+fn part1(rounds: &[(i32, i32)]) -> i32 {
+    rounds
+        .iter()
+        .map(|&(first, second)| {
             (second + 1)
                 + if (first + 1) % 3 == second {
                     6
@@ -51,47 +46,92 @@ fn part1(input: &str) -> i32 {
         .sum()
 }
 
-fn part2(input: &str) -> i32 {
-    input
-        .lines()
-        .map(|line| {
-            let mut moves = line.split_ascii_whitespace().take(2).map(parse_move);
-            let first = moves.next().expect("Valid Move");
-            let second = moves.next().expect("Valid Move");
-
-            (second * 3) + (((second - 1) + first) % 3) + 1
-        })
+fn part2(rounds: &[(i32, i32)]) -> i32 {
+    rounds
+        .iter()
+        .map(|&(first, second)| (second * 3) + ((first + second + 2) % 3) + 1)
         .sum()
 }
 
-pub fn solve() {
-    let input = std::fs::read_to_string("input/day2.txt").expect("Need input");
+pub struct Day2Better;
+
+impl Solution for Day2Better {
+    type Input = Vec<(i32, i32)>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input, parse_line)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Part1> {
+        Ok(part1(input))
+    }
 
-    let score = part1(&input);
-    println!("Part 1 Score: {}", score);
-    let score = part2(&input);
-    println!("Part 2 Score: {}", score);
+    fn part2(&self, input: &Self::Input) -> Result<Self::Part2> {
+        Ok(part2(input))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::day2::Day2;
+    use crate::generate::{self, Rng};
+    use crate::solution::Runner;
+
+    const EXAMPLE: &str = include_str!("../examples/day2/example.txt");
 
     #[test]
     fn part1_test() {
-        let input = "A Y
-B X
-C Z";
+        let input = EXAMPLE;
 
-        assert_eq!(15, part1(input));
+        assert_eq!(15, part1(&parse_lines(input, parse_line).unwrap()));
     }
 
     #[test]
     fn part2_test() {
-        let input = "A Y
-B X
-C Z";
+        let input = EXAMPLE;
 
-        assert_eq!(12, part2(input));
+        assert_eq!(12, part2(&parse_lines(input, parse_line).unwrap()));
+    }
+
+    #[test]
+    fn part2_lose_against_rock_test() {
+        assert_eq!(3, part2(&[(0, 0)]));
+    }
+
+    #[test]
+    fn parse_error_test() {
+        let err = parse_lines("A Y\nB X\nC  W", parse_line).unwrap_err();
+        assert_eq!((3, 4, "W"), (err.line, err.column, err.text.as_str()));
+    }
+
+    #[test]
+    fn matches_day2_on_generated_guides() {
+        let mut rng = Rng::new(2022);
+        for _ in 0..5000 {
+            let rounds = rng.below(40) as usize + 1;
+            let guide = generate::strategy_guide(&mut rng, rounds);
+
+            let expected = Day2::default().run(&guide, None).unwrap().answers();
+            let actual = Day2Better.run(&guide, None).unwrap().answers();
+            assert_eq!(expected, actual, "guide:\n{}", guide);
+        }
+    }
+
+    #[test]
+    fn matches_day2_on_every_single_round() {
+        for first in ["A", "B", "C"] {
+            for second in ["X", "Y", "Z"] {
+                let guide = format!("{} {}", first, second);
+                assert_eq!(
+                    Day2::default().run(&guide, None).unwrap().answers(),
+                    Day2Better.run(&guide, None).unwrap().answers(),
+                    "{}",
+                    guide
+                );
+            }
+        }
     }
 }
@@ -1,37 +1,81 @@
+use anyhow::{bail, Context, Result};
 use itertools::Itertools;
-use std::{borrow::BorrowMut, collections::HashSet};
+use std::io::BufRead;
+
+use crate::parse::{for_each_line, parse_lines, ParseError};
+use crate::solution::{Solution, Streaming};
+
+/// A set of items, one bit for each item's priority. Items are only ever
+/// `a-z` and `A-Z`, so 52 of the 64 bits are enough.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Items(u64);
+
+impl Items {
+    /// The items in `s`, which has to hold only ASCII letters.
+    pub fn of(s: &str) -> Items {
+        Items(s.chars().fold(0, |bits, c| bits | 1 << char_score(c)))
+    }
+
+    #[cfg(test)]
+    pub fn contains(self, c: char) -> bool {
+        self.0 & 1 << char_score(c) != 0
+    }
+
+    pub fn intersection(self, other: Items) -> Items {
+        Items(self.0 & other.0)
+    }
+
+    pub fn union(self, other: Items) -> Items {
+        Items(self.0 | other.0)
+    }
+
+    pub fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Every item in the set, lowest priority first.
+    pub fn iter(self) -> impl Iterator<Item = char> {
+        (1..=52)
+            .filter(move |&score| self.0 & 1 << score != 0)
+            .map(score_char)
+    }
+}
 
 #[derive(Debug, PartialEq)]
-struct Rucksack<'a> {
-    first: &'a str,
-    first_hash: HashSet<char>,
-    second: &'a str,
-    second_hash: HashSet<char>,
+pub struct Rucksack {
+    first_items: Items,
+    second_items: Items,
 }
 
-impl<'a> From<&'a str> for Rucksack<'a> {
-    fn from(s: &'a str) -> Self {
-        if s.len() % 2 != 0 {
-            panic!("Compartments different sizes");
-        }
+impl TryFrom<&str> for Rucksack {
+    type Error = ParseError;
 
-        let first = &s[0..(s.len() / 2)];
-        let second = &s[(s.len() / 2..)];
-        Rucksack {
-            first,
-            first_hash: HashSet::from_iter(first.chars()),
-            second,
-            second_hash: HashSet::from_iter(second.chars()),
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        if let Some((i, c)) = s.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+            return Err(ParseError::new(s, &s[i..i + c.len_utf8()], "Invalid item"));
+        }
+        if !s.len().is_multiple_of(2) {
+            return Err(ParseError::new(s, s, "Compartments different sizes"));
         }
+
+        let (first, second) = s.split_at(s.len() / 2);
+        Ok(Rucksack {
+            first_items: Items::of(first),
+            second_items: Items::of(second),
+        })
     }
 }
 
-impl<'a> Rucksack<'a> {
-    pub fn common_item(&self) -> char {
-        self.first
-            .chars()
-            .find(|c| self.second_hash.contains(c))
-            .expect("Should be a match")
+impl Rucksack {
+    pub fn items(&self) -> Items {
+        self.first_items.union(self.second_items)
+    }
+
+    pub fn common_item(&self) -> Option<char> {
+        self.first_items
+            .intersection(self.second_items)
+            .iter()
+            .next()
     }
 }
 
@@ -43,92 +87,191 @@ fn char_score(c: char) -> u32 {
     }
 }
 
-fn part1(input: &str) -> u32 {
-    input
-        .lines()
-        .map(|line| Rucksack::from(line).common_item())
-        .map(char_score)
+fn score_char(score: u32) -> char {
+    match score {
+        1..=26 => char::from(b'a' + score as u8 - 1),
+        _ => char::from(b'A' + score as u8 - 27),
+    }
+}
+
+fn part1(sacks: &[Rucksack]) -> Result<u32> {
+    sacks
+        .iter()
+        .enumerate()
+        .map(|(i, sack)| {
+            sack.common_item()
+                .map(char_score)
+                .with_context(|| format!("Line {}: no item in both compartments", i + 1))
+        })
         .sum()
 }
 
-struct Group<'a>(Rucksack<'a>, Rucksack<'a>, Rucksack<'a>);
+/// The rucksacks of a group of elves, as the items in each.
+struct Group(Vec<Items>);
 
-impl<'a> From<&'a str> for Group<'a> {
-    fn from(s: &'a str) -> Self {
-        let mut sacks = s.lines().take(3).map(Rucksack::from);
+impl Group {
+    /// Every item that all the rucksacks of the group hold.
+    pub fn common_items(&self) -> Items {
+        self.0
+            .iter()
+            .copied()
+            .reduce(Items::intersection)
+            .unwrap_or_default()
+    }
 
-        Group(
-            sacks.next().expect("Not enough sacks"),
-            sacks.next().expect("Not enough sacks"),
-            sacks.next().expect("Not enough sacks"),
-        )
+    /// The one item every rucksack holds.
+    pub fn badge(&self) -> Result<char> {
+        let common = self.common_items();
+        match common.count() {
+            0 => bail!("No item is in every rucksack of the group"),
+            1 => Ok(common.iter().next().unwrap()),
+            n => bail!(
+                "{} items are in every rucksack of the group, expected one badge: {}",
+                n,
+                common.iter().join(", ")
+            ),
+        }
     }
 }
 
-impl<'a> Group<'a> {
-    pub fn common_item(&self) -> char {
-        self.0
-            .first
-            .chars()
-            .find(|c| {
-                (self.1.first_hash.contains(c) || self.1.second_hash.contains(c))
-                    && (self.2.first_hash.contains(c) || self.2.second_hash.contains(c))
-            })
-            .unwrap_or_else(|| {
-                self.0
-                    .second
-                    .chars()
-                    .find(|c| {
-                        (self.1.first_hash.contains(c) || self.1.second_hash.contains(c))
-                            && (self.2.first_hash.contains(c) || self.2.second_hash.contains(c))
-                    })
-                    .expect("No match")
-            })
-    }
-}
-
-fn part2(input: &str) -> u32 {
-    input
-        .lines()
-        .chunks(3)
-        .into_iter()
-        .borrow_mut()
-        .map(|mut c| c.join("\n"))
-        .map(|chunk| {
-            let s = &chunk as &str;
-            Group::from(s).common_item()
+/// Where the `i`th group of `size` rucksacks is in the input, for errors.
+fn group_lines(i: usize, size: usize) -> String {
+    format!("Lines {}-{}", size * i + 1, size * (i + 1))
+}
+
+fn part2(sacks: &[Rucksack], group_size: usize) -> Result<u32> {
+    if !sacks.len().is_multiple_of(group_size) {
+        bail!(
+            "{} rucksacks can't be split into groups of {}",
+            sacks.len(),
+            group_size
+        );
+    }
+
+    sacks
+        .chunks(group_size)
+        .enumerate()
+        .map(|(i, group)| {
+            let badge = Group(group.iter().map(Rucksack::items).collect())
+                .badge()
+                .with_context(|| group_lines(i, group_size))?;
+            Ok(char_score(badge))
         })
-        .map(char_score)
         .sum()
 }
 
-pub fn solve() {
-    let input = std::fs::read_to_string("input/day3.txt").expect("Need input");
+/// Day 3, with elves in groups of `group_size`.
+pub struct Day3 {
+    group_size: usize,
+}
+
+impl Day3 {
+    pub fn new(group_size: usize) -> Result<Day3> {
+        if group_size == 0 {
+            bail!("Groups need at least one elf");
+        }
+        Ok(Day3 { group_size })
+    }
+}
+
+impl Default for Day3 {
+    fn default() -> Self {
+        Day3 { group_size: 3 }
+    }
+}
+
+impl Solution for Day3 {
+    type Input = Vec<Rucksack>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input, Rucksack::try_from)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Part1> {
+        part1(input)
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Self::Part2> {
+        part2(input, self.group_size)
+    }
+}
+
+impl Streaming for Day3 {
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn stream_part1(&self, input: &mut dyn BufRead) -> Result<Self::Part1> {
+        let mut total = 0;
+        for_each_line(input, |line| {
+            let item = Rucksack::try_from(line)?
+                .common_item()
+                .context("No item in both compartments")?;
+            total += i64::from(char_score(item));
+            Ok(())
+        })?;
+        Ok(total)
+    }
+
+    fn stream_part2(&self, input: &mut dyn BufRead) -> Result<Self::Part2> {
+        let mut total = 0;
+        let mut lines = 0;
+        // only the items of the current group are kept
+        let mut group = Group(Vec::with_capacity(self.group_size));
+        // not for_each_line, which would blame a bad group on its last line
+        for line in input.lines() {
+            lines += 1;
+            let line = line.with_context(|| format!("Failed to read line {}", lines))?;
+            let sack = Rucksack::try_from(line.as_str()).map_err(|e| e.at_line(lines))?;
+            group.0.push(sack.items());
 
-    let score = part1(&input);
-    println!("[Day  3][Part 1] - {}", score);
-    let score = part2(&input);
-    println!("[Day  3][Part 2] - {}", score);
+            if group.0.len() == self.group_size {
+                let badge = group
+                    .badge()
+                    .with_context(|| group_lines(lines / self.group_size - 1, self.group_size))?;
+                total += i64::from(char_score(badge));
+                group.0.clear();
+            }
+        }
+
+        if !group.0.is_empty() {
+            bail!(
+                "{} rucksacks can't be split into groups of {}",
+                lines,
+                self.group_size
+            );
+        }
+        Ok(total)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generate::{self, Rng};
+    use crate::solution::{Answer, Runner, StreamRunner};
+
+    const EXAMPLE: &str = include_str!("../examples/day3/example.txt");
+
+    fn sacks(input: &str) -> Vec<Rucksack> {
+        parse_lines(input, Rucksack::try_from).unwrap()
+    }
 
     #[test]
     fn parse_rucksack_test() {
         let input = "vJrwpWtwJgWrhcsFMMfFFhFp";
-        let rucksack = Rucksack::from(input);
-        assert_eq!("vJrwpWtwJgWr", rucksack.first);
-        assert_eq!("hcsFMMfFFhFp", rucksack.second);
+        let rucksack = Rucksack::try_from(input).unwrap();
+        assert_eq!(Items::of("vJrwpWtwJgWr"), rucksack.first_items);
+        assert_eq!(Items::of("hcsFMMfFFhFp"), rucksack.second_items);
     }
 
     #[test]
     fn common_item_test() {
         let input = "vJrwpWtwJgWrhcsFMMfFFhFp";
-        let rucksack = Rucksack::from(input);
+        let rucksack = Rucksack::try_from(input).unwrap();
 
-        assert_eq!('p', rucksack.common_item());
+        assert_eq!(Some('p'), rucksack.common_item());
     }
 
     #[test]
@@ -137,17 +280,34 @@ mod tests {
         assert_eq!(26, char_score('z'));
         assert_eq!(27, char_score('A'));
         assert_eq!(52, char_score('Z'));
+        for score in 1..=52 {
+            assert_eq!(score, char_score(score_char(score)));
+        }
+    }
+
+    #[test]
+    fn items_test() {
+        let first = Items::of("vJrwpWtwJgWr");
+        let second = Items::of("hcsFMMfFFhFp");
+        assert_eq!(8, first.count());
+        assert!(first.contains('J') && !first.contains('j'));
+
+        let common = first.intersection(second);
+        assert_eq!(vec!['p'], common.iter().collect::<Vec<_>>());
+        assert_eq!(8 + 7 - 1, first.union(second).count());
+
+        assert_eq!(0, Items::default().count());
+        assert_eq!(0, Items::of("abc").intersection(Items::of("ABC")).count());
+        assert_eq!(
+            vec!['a', 'z', 'A', 'Z'],
+            Items::of("ZAza").iter().collect::<Vec<_>>()
+        );
     }
 
     #[test]
     fn part1_test() {
-        let input = "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw";
-        assert_eq!(157, part1(input));
+        let input = EXAMPLE;
+        assert_eq!(157, part1(&sacks(input)).unwrap());
     }
 
     #[test]
@@ -156,21 +316,127 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
 PmmdzqPrVvPwwTWBwg";
 
-        let group = Group::from(input);
-        let common = group.common_item();
-        assert_eq!('r', common);
+        let group = Group(
+            parse_lines(input, Rucksack::try_from)
+                .unwrap()
+                .iter()
+                .map(Rucksack::items)
+                .collect(),
+        );
+        assert_eq!(vec!['r'], group.common_items().iter().collect::<Vec<_>>());
+        assert_eq!('r', group.badge().unwrap());
     }
 
     #[test]
     fn part2_test() {
-        let input = "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw";
+        let input = EXAMPLE;
 
-        let score = part2(input);
+        let score = part2(&sacks(input), 3).unwrap();
         assert_eq!(70, score);
     }
+
+    #[test]
+    fn streaming_matches_parsed() {
+        let lines = || generate::rucksack_lines(Rng::new(11), 1000);
+        let input = lines().collect::<Vec<_>>().join("\n");
+        assert_eq!(
+            Day3::default().run(&input, None).unwrap().answers(),
+            generate::stream_answers(&Day3::default(), lines)
+        );
+    }
+
+    #[test]
+    fn streams_large_input() {
+        let groups: i64 = 20_000;
+        let answers = generate::stream_answers(&Day3::default(), || {
+            generate::rucksack_lines(Rng::new(3), groups as usize)
+        });
+        let [(_, Answer::Number(items)), (_, Answer::Number(badges))] = answers[..] else {
+            panic!("{:?}", answers);
+        };
+        assert!(items <= 52 * 3 * groups && badges <= 52 * groups);
+    }
+
+    #[test]
+    fn stream_errors() {
+        let stream = |input: &'static str, part| {
+            let mut open = || -> Result<Box<dyn BufRead>> { Ok(Box::new(input.as_bytes())) };
+            Day3::default().stream(&mut open, Some(part)).unwrap_err()
+        };
+        let err = stream("abca\nab", 1);
+        assert_eq!("Line 2: No item in both compartments", format!("{:#}", err));
+
+        let err = stream("aa\nab1c", 1);
+        assert_eq!(2, err.downcast::<ParseError>().unwrap().line);
+
+        let err = stream("aa\nbb\ncc", 2);
+        assert_eq!(
+            "Lines 1-3: No item is in every rucksack of the group",
+            format!("{:#}", err)
+        );
+        assert_eq!(
+            format!("{:#}", part2(&sacks("aa\nbb\ncc"), 3).unwrap_err()),
+            format!("{:#}", err)
+        );
+
+        let err = stream("aa\naa", 2);
+        assert_eq!(
+            "2 rucksacks can't be split into groups of 3",
+            format!("{:#}", err)
+        );
+    }
+
+    #[test]
+    fn parse_errors_test() {
+        let err = Rucksack::try_from("vJrwp WtwJgWr").unwrap_err();
+        assert_eq!((6, " "), (err.column, err.text.as_str()));
+
+        let err = parse_lines("vJrwpWtwJgWrhcsFMMfFFhFp\nabc", Rucksack::try_from).unwrap_err();
+        assert_eq!(
+            (2, "Compartments different sizes"),
+            (err.line, err.message.as_str())
+        );
+
+        assert!(part2(&sacks("vJrwpWtwJgWrhcsFMMfFFhFp"), 3).is_err());
+    }
+
+    #[test]
+    fn group_sizes() {
+        // the first two rucksacks of each example group share more than the badge
+        let lines: Vec<&str> = EXAMPLE.lines().collect();
+        let pairs = [lines[0], lines[1], lines[3], lines[4]].join("\n");
+        let err = part2(&sacks(&pairs), 2).unwrap_err();
+        assert_eq!(
+            "Lines 1-2: 5 items are in every rucksack of the group, expected one badge: f, r, s, F, M",
+            format!("{:#}", err)
+        );
+
+        // on its own, a rucksack's only badge candidate is everything in it
+        assert!(part2(&sacks("abcabc"), 1).is_err());
+        assert_eq!(1, part2(&sacks("aa"), 1).unwrap());
+
+        let input = "aXbY\nbZaW\ncVaU\naTdS";
+        assert_eq!(1, part2(&sacks(input), 4).unwrap());
+        assert_eq!(
+            vec![(2, Answer::Number(1))],
+            Day3::new(4).unwrap().run(input, Some(2)).unwrap().answers()
+        );
+        let mut open = || -> Result<Box<dyn BufRead>> { Ok(Box::new(input.as_bytes())) };
+        assert_eq!(
+            vec![(2, Answer::Number(1))],
+            Day3::new(4)
+                .unwrap()
+                .stream(&mut open, Some(2))
+                .unwrap()
+                .answers()
+        );
+
+        assert!(Day3::new(0).is_err());
+
+        let err = part2(&sacks(input), 3).unwrap_err();
+        assert_eq!(
+            "4 rucksacks can't be split into groups of 3",
+            err.to_string()
+        );
+    }
 }
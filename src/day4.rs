@@ -1,10 +1,15 @@
+use anyhow::Result;
 use num::Num;
 use std::cmp::Ordering::*;
 use std::fmt::Debug;
+use std::io::BufRead;
 use std::str::FromStr;
 
+use crate::parse::{for_each_line, parse_lines, ParseError};
+use crate::solution::{Solution, Streaming};
+
 #[derive(Debug, PartialEq, Clone, Copy)]
-struct Interval<T: Debug + Num + PartialEq + PartialOrd> {
+pub struct Interval<T: Debug + Num + PartialEq + PartialOrd> {
     min: T,
     max: T,
 }
@@ -45,58 +50,106 @@ impl<T: Debug + Num + PartialEq + PartialOrd> Interval<T> {
 }
 
 impl<T: Debug + Num + PartialEq + PartialOrd + FromStr> FromStr for Interval<T> {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut nums = s.split('-').take(2).filter_map(|num| num.parse().ok());
-
-        let min = nums.next().ok_or("Not enough numbers")?;
-        let max = nums.next().ok_or("Not enough numbers")?;
+        let (min, max) = s
+            .split_once('-')
+            .ok_or_else(|| ParseError::new(s, s, "Not an interval like 2-4"))?;
+
+        let min = min
+            .parse()
+            .map_err(|_| ParseError::new(s, min, "Invalid number"))?;
+        let max = max
+            .parse()
+            .map_err(|_| ParseError::new(s, max, "Invalid number"))?;
+        if min > max {
+            return Err(ParseError::new(s, s, "Interval ends before it starts"));
+        }
 
         Ok(Interval { min, max })
     }
 }
 
-fn part1(input: &str) -> u32 {
-    input
-        .lines()
-        .map(|s| {
-            let mut intervals = s.split(',').filter_map(|s| s.parse().ok());
-            let first: Interval<u32> = intervals.next().expect("Valid interval");
-            let second: Interval<u32> = intervals.next().expect("Valid interval");
-
-            first.contains(&second) || second.contains(&first)
-        })
-        .filter(|&p| p)
-        .count() as u32
+type Pair = (Interval<u32>, Interval<u32>);
+
+fn parse_pair(line: &str) -> Result<Pair, ParseError> {
+    let (first, second) = line
+        .split_once(',')
+        .ok_or_else(|| ParseError::new(line, line, "Not two intervals separated by a comma"))?;
+
+    let parse = |token: &str| token.parse().map_err(|e: ParseError| e.within(line, token));
+    Ok((parse(first)?, parse(second)?))
+}
+
+fn either_contains((first, second): &Pair) -> bool {
+    first.contains(second) || second.contains(first)
+}
+
+fn overlapping((first, second): &Pair) -> bool {
+    first.overlaps(second) || second.contains(first)
+}
+
+fn part1(pairs: &[Pair]) -> u32 {
+    pairs.iter().filter(|pair| either_contains(pair)).count() as u32
+}
+
+fn part2(pairs: &[Pair]) -> u32 {
+    pairs.iter().filter(|pair| overlapping(pair)).count() as u32
+}
+
+/// Counts the pairs in `input` matching `f` without keeping any of them.
+fn stream_count(input: &mut dyn BufRead, f: fn(&Pair) -> bool) -> Result<u64> {
+    let mut count = 0;
+    for_each_line(input, |line| {
+        if f(&parse_pair(line)?) {
+            count += 1;
+        }
+        Ok(())
+    })?;
+    Ok(count)
 }
 
-fn part2(input: &str) -> u32 {
-    input
-        .lines()
-        .map(|s| {
-            let mut intervals = s.split(',').filter_map(|s| s.parse().ok());
-            let first: Interval<u32> = intervals.next().expect("Valid interval");
-            let second: Interval<u32> = intervals.next().expect("Valid interval");
-
-            first.overlaps(&second) || second.contains(&first)
-        })
-        .filter(|&p| p)
-        .count() as u32
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Pair>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input, parse_pair)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Part1> {
+        Ok(part1(input))
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Self::Part2> {
+        Ok(part2(input))
+    }
 }
 
-pub fn solve() {
-    let input = std::fs::read_to_string("input/day4.txt").expect("Need input");
+impl Streaming for Day4 {
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn stream_part1(&self, input: &mut dyn BufRead) -> Result<Self::Part1> {
+        Ok(stream_count(input, either_contains)?.try_into()?)
+    }
 
-    let score = part1(&input);
-    println!("[Day  4][Part 1] - {}", score);
-    let score = part2(&input);
-    println!("[Day  4][Part 2] - {}", score);
+    fn stream_part2(&self, input: &mut dyn BufRead) -> Result<Self::Part2> {
+        Ok(stream_count(input, overlapping)?.try_into()?)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generate::{self, Rng};
+    use crate::solution::{Answer, Runner};
+
+    const EXAMPLE: &str = include_str!("../examples/day4/example.txt");
 
     #[test]
     fn interval_ordering() {
@@ -158,25 +211,53 @@ mod tests {
 
     #[test]
     fn part1_test() {
-        let input = "2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8";
+        let input = EXAMPLE;
 
-        assert_eq!(2, part1(input));
+        assert_eq!(2, part1(&parse_lines(input, parse_pair).unwrap()));
     }
 
     #[test]
     fn part2_test() {
-        let input = "2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8";
-
-        assert_eq!(4, part2(input));
+        let input = EXAMPLE;
+
+        assert_eq!(4, part2(&parse_lines(input, parse_pair).unwrap()));
+    }
+
+    #[test]
+    fn streaming_matches_parsed() {
+        let lines = || generate::section_pairs(Rng::new(11), 5000);
+        let input = lines().collect::<Vec<_>>().join("\n");
+        assert_eq!(
+            Day4.run(&input, None).unwrap().answers(),
+            generate::stream_answers(&Day4, lines)
+        );
+    }
+
+    #[test]
+    fn streams_large_input() {
+        let pairs = 400_000;
+        let answers =
+            generate::stream_answers(&Day4, || generate::section_pairs(Rng::new(3), pairs));
+        let [(_, Answer::Number(contained)), (_, Answer::Number(overlapping))] = answers[..] else {
+            panic!("{:?}", answers);
+        };
+        assert!(0 < contained && contained <= overlapping && overlapping <= pairs as i64);
+    }
+
+    #[test]
+    fn parse_errors_test() {
+        let err = parse_lines("2-4,6-8\n2-3,4-x", parse_pair).unwrap_err();
+        assert_eq!((2, 7, "x"), (err.line, err.column, err.text.as_str()));
+
+        let err = parse_pair("2-4 6-8").unwrap_err();
+        assert_eq!((1, "2-4 6-8"), (err.column, err.text.as_str()));
+
+        let err = parse_pair("2-4,8-6").unwrap_err();
+        assert_eq!(
+            (5, "Interval ends before it starts"),
+            (err.column, err.message.as_str())
+        );
+
+        assert!("2-4 ".parse::<Interval<u32>>().is_err());
     }
 }
@@ -4,31 +4,50 @@ use anyhow::{bail, Context, Result};
 use lazy_static::lazy_static;
 use regex::Regex;
 
+use crate::parse::{parse_lines, ParseError};
+use crate::solution::Solution;
+
 #[derive(Debug, PartialEq)]
-struct Instruction {
+pub struct Instruction {
     num: u32,
     from: u32,
     to: u32,
+    /// 1-based line of the input, `0` until the caller knows which line it was.
+    line: usize,
 }
 
 impl FromStr for Instruction {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         lazy_static! {
-            static ref RE: Regex = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
+            static ref RE: Regex = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
         }
 
-        let captures = RE.captures_iter(s).next().ok_or("No matches")?;
+        let captures = RE
+            .captures(s)
+            .ok_or_else(|| ParseError::new(s, s, "Not an instruction like move N from A to B"))?;
+        let number = |i: usize| {
+            let text = &s[captures.get(i).map_or(0..0, |m| m.range())];
+            text.parse::<u32>()
+                .map_err(|_| ParseError::new(s, text, "Number too large"))
+                .and_then(|n| match n {
+                    0 if i > 1 => Err(ParseError::new(s, text, "Stacks are numbered from 1")),
+                    n => Ok(n),
+                })
+        };
 
-        let num = captures[1].parse().map_err(|_| "Not a num")?;
-        let from = captures[2].parse().map_err(|_| "Not a num")?;
-        let to = captures[3].parse().map_err(|_| "Not a num")?;
-        Ok(Instruction { num, from, to })
+        Ok(Instruction {
+            num: number(1)?,
+            from: number(2)?,
+            to: number(3)?,
+            line: 0,
+        })
     }
 }
 
-struct Board {
+#[derive(Clone)]
+pub struct Board {
     num: u32,
     stacks: Vec<Vec<String>>,
 }
@@ -40,9 +59,12 @@ impl Board {
         Board { num, stacks }
     }
     pub fn execute(&mut self, instruction: &Instruction, in_order: bool) -> Result<()> {
-        if instruction.from as usize > self.stacks.len()
-            || instruction.to as usize > self.stacks.len()
-        {
+        self.move_crates(instruction, in_order)
+            .with_context(|| format!("Line {}", instruction.line))
+    }
+
+    fn move_crates(&mut self, instruction: &Instruction, in_order: bool) -> Result<()> {
+        if instruction.from > self.num || instruction.to > self.num {
             bail!("Invalid location");
         }
 
@@ -51,7 +73,7 @@ impl Board {
             for _ in 0..instruction.num {
                 let obj = self.stacks[instruction.from as usize - 1]
                     .pop()
-                    .context("Not enough items")?;
+                    .with_context(|| format!("Not enough items on stack {}", instruction.from))?;
 
                 temp.push(obj);
             }
@@ -64,7 +86,7 @@ impl Board {
             for _ in 0..instruction.num {
                 let obj = self.stacks[instruction.from as usize - 1]
                     .pop()
-                    .context("Not enough items")?;
+                    .with_context(|| format!("Not enough items on stack {}", instruction.from))?;
 
                 self.stacks[instruction.to as usize - 1].push(obj);
             }
@@ -75,25 +97,35 @@ impl Board {
 }
 
 impl FromStr for Board {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let lines: Vec<&str> = s.lines().collect();
 
-        let num: u32 = lines
+        let numbers = lines
             .last()
-            .ok_or("Not enough lines")?
-            .split_ascii_whitespace()
-            .last()
-            .ok_or("Not enough characters")?
-            .parse()
-            .map_err(|_| "Not a number")?;
+            .ok_or_else(|| ParseError::missing(s, "Missing stacks").at_line(1))?;
+        let num = stack_count(numbers).map_err(|e| e.at_line(lines.len()))?;
+
+        // Each stack takes 4 columns, so a wider row has crates in no stack.
+        for (i, row) in lines[..lines.len() - 1].iter().enumerate() {
+            if let Some((extra, _)) = row.char_indices().nth(4 * num as usize) {
+                return Err(ParseError::new(
+                    row,
+                    &row[extra..],
+                    format!("There are only {} stacks", num),
+                )
+                .at_line(i + 1));
+            }
+        }
 
         let mut board = Board::new(num);
 
         for i in (0..lines.len() - 1).rev() {
             for (c_i, x) in (1..(4 * num)).step_by(4).enumerate() {
-                let c = lines[i].chars().nth(x as usize).ok_or("Not enough chars")?;
+                // Editors often strip the trailing spaces of a row, so a
+                // short row just means the stacks to the right are empty there.
+                let c = lines[i].chars().nth(x as usize).unwrap_or(' ');
                 if !c.is_whitespace() {
                     let s = String::from(c);
                     board.stacks[c_i].push(s);
@@ -105,63 +137,130 @@ impl FromStr for Board {
     }
 }
 
-fn parse_input(input: &str) -> Result<(Board, Vec<Instruction>)> {
-    let mut split = input.split("\n\n");
-
-    let board = split
-        .next()
-        .context("Malformed input".to_owned())?
-        .parse::<Board>()
-        .ok()
-        .context("Not a board")?;
+/// Checks the row of stack numbers reads `1 2 .. n`, each under the middle
+/// of its stack, and returns `n`.
+fn stack_count(numbers: &str) -> Result<u32, ParseError> {
+    let mut num: u32 = 0;
+    for token in numbers.split_ascii_whitespace() {
+        let expected = num + 1;
+        if token != expected.to_string() {
+            return Err(ParseError::new(
+                numbers,
+                token,
+                format!("Expected stack number {}", expected),
+            ));
+        }
+        let start = token.as_ptr() as usize - numbers.as_ptr() as usize;
+        let middle = 4 * num as usize + 1;
+        if !(start..start + token.len()).contains(&middle) {
+            return Err(ParseError::new(
+                numbers,
+                token,
+                format!("Stack number should be in column {}", middle + 1),
+            ));
+        }
+        num = expected;
+    }
+    if num == 0 {
+        return Err(ParseError::missing(numbers, "Missing stack numbers"));
+    }
+    Ok(num)
+}
 
-    let instructions = split
-        .next()
-        .map(|s| s.lines().filter_map(|line| line.parse().ok()))
-        .context("No instructions")?
-        .collect::<Vec<Instruction>>();
+fn parse_input(input: &str) -> Result<(Board, Vec<Instruction>), ParseError> {
+    let (board, instructions) = input.split_once("\n\n").ok_or_else(|| {
+        let last = input.lines().last().unwrap_or(input);
+        ParseError::missing(last, "Missing instructions").at_line(input.lines().count())
+    })?;
+
+    let board = board.parse::<Board>()?;
+
+    // Instructions start after the board and the blank line separating them.
+    let offset = board_lines(input) + 1;
+    let mut line_no = offset;
+    let instructions = parse_lines(instructions, |line| {
+        line_no += 1;
+        let instruction = Instruction {
+            line: line_no,
+            ..line.parse()?
+        };
+        // The pattern only allows single spaces, so the stacks are words 3 and 5.
+        let words: Vec<&str> = line.split(' ').collect();
+        for (stack, word) in [(instruction.from, words[3]), (instruction.to, words[5])] {
+            if stack > board.num {
+                return Err(ParseError::new(
+                    line,
+                    word,
+                    format!("There are only {} stacks", board.num),
+                ));
+            }
+        }
+        Ok(instruction)
+    })
+    .map_err(|e| {
+        let line = e.line + offset;
+        e.at_line(line)
+    })?;
 
     Ok((board, instructions))
 }
 
-fn part1(input: &str) -> String {
-    let (mut board, instructions) = parse_input(input).expect("Valid input");
-    for inst in &instructions {
-        board.execute(inst, false).expect("Succeeded");
+fn board_lines(input: &str) -> usize {
+    input.lines().take_while(|line| !line.is_empty()).count()
+}
+
+fn part1(board: &Board, instructions: &[Instruction]) -> Result<String> {
+    let mut board = board.clone();
+    for inst in instructions {
+        board.execute(inst, false)?;
     }
 
-    board
+    Ok(board
         .stacks
         .iter()
         .map(|stack| stack.last().map_or(String::new(), |s| s.clone()))
-        .collect::<String>()
+        .collect::<String>())
 }
 
-fn part2(input: &str) -> String {
-    let (mut board, instructions) = parse_input(input).expect("Valid input");
-    for inst in &instructions {
-        board.execute(inst, true).expect("Succeeded");
+fn part2(board: &Board, instructions: &[Instruction]) -> Result<String> {
+    let mut board = board.clone();
+    for inst in instructions {
+        board.execute(inst, true)?;
     }
 
-    board
+    Ok(board
         .stacks
         .iter()
         .map(|stack| stack.last().map_or(String::new(), |s| s.clone()))
-        .collect::<String>()
+        .collect::<String>())
 }
 
-pub fn solve() {
-    let input = std::fs::read_to_string("input/day5.txt").expect("Need input");
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = (Board, Vec<Instruction>);
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part1(&self, (board, instructions): &Self::Input) -> Result<Self::Part1> {
+        part1(board, instructions)
+    }
 
-    let score = part1(&input);
-    println!("[Day  5][Part 1] - {}", score);
-    let score = part2(&input);
-    println!("[Day  5][Part 2] - {}", score);
+    fn part2(&self, (board, instructions): &Self::Input) -> Result<Self::Part2> {
+        part2(board, instructions)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solution::{Answer, Runner};
+
+    const EXAMPLE: &str = include_str!("../examples/day5/example.txt");
 
     #[test]
     fn execute_inst_test() {
@@ -172,6 +271,7 @@ mod tests {
             num: 1,
             from: 1,
             to: 2,
+            line: 0,
         };
 
         stacks.execute(&inst, false).unwrap();
@@ -208,7 +308,8 @@ mod tests {
             Instruction {
                 num: 1,
                 from: 2,
-                to: 1
+                to: 1,
+                line: 0
             },
             instruction
         );
@@ -216,15 +317,7 @@ mod tests {
 
     #[test]
     fn parse_input_test() {
-        let input = "    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
-
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2";
+        let input = EXAMPLE;
 
         let (board, instructions) = parse_input(input).unwrap();
         assert_eq!(3, board.num);
@@ -243,7 +336,8 @@ move 1 from 1 to 2";
             Instruction {
                 num: 1,
                 from: 2,
-                to: 1
+                to: 1,
+                line: 6
             },
             instructions[0]
         );
@@ -251,7 +345,8 @@ move 1 from 1 to 2";
             Instruction {
                 num: 3,
                 from: 1,
-                to: 3
+                to: 3,
+                line: 7
             },
             instructions[1]
         );
@@ -259,7 +354,8 @@ move 1 from 1 to 2";
             Instruction {
                 num: 2,
                 from: 2,
-                to: 1
+                to: 1,
+                line: 8
             },
             instructions[2]
         );
@@ -267,7 +363,8 @@ move 1 from 1 to 2";
             Instruction {
                 num: 1,
                 from: 1,
-                to: 2
+                to: 2,
+                line: 9
             },
             instructions[3]
         );
@@ -275,29 +372,82 @@ move 1 from 1 to 2";
 
     #[test]
     fn part1_test() {
-        let input = "    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
-
-move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2";
-        assert_eq!("CMZ", part1(input));
+        let (board, instructions) = parse_input(EXAMPLE).unwrap();
+        assert_eq!("CMZ", part1(&board, &instructions).unwrap());
     }
 
     #[test]
     fn part2_test() {
-        let input = "    [D]    
-[N] [C]    
-[Z] [M] [P]
- 1   2   3 
+        let (board, instructions) = parse_input(EXAMPLE).unwrap();
+        assert_eq!("MCD", part2(&board, &instructions).unwrap());
+    }
+
+    #[test]
+    fn parse_errors_test() {
+        let err = "move 1 from 0 to 1".parse::<Instruction>().unwrap_err();
+        assert_eq!((13, "0"), (err.column, err.text.as_str()));
+
+        let input = "[Z] [M] [P]
+ 1   2   3
 
 move 1 from 2 to 1
-move 3 from 1 to 3
-move 2 from 2 to 1
-move 1 from 1 to 2";
-        assert_eq!("MCD", part2(input));
+mvoe 3 from 1 to 3";
+        let err = parse_input(input).err().unwrap();
+        assert_eq!((5, 1), (err.line, err.column));
+
+        let err = parse_input("[Z] [M] [P]\n 1   2   x").err().unwrap();
+        assert_eq!("Missing instructions", err.message);
+        let err = parse_input("    [D]\n 1   2\nmove 1 from 1 to 2")
+            .err()
+            .unwrap();
+        assert_eq!((3, 19), (err.line, err.column));
+
+        let err = parse_input("[Z] [M]\n 1   2\n\nmove 1 from 1 to 2\nmove 1 from 3 to 1")
+            .err()
+            .unwrap();
+        assert_eq!(
+            "line 5, column 13: There are only 2 stacks `3`",
+            err.to_string()
+        );
+
+        let err = parse_input("[Z] [M] [P]\n 1   2   x\n\nmove 1 from 2 to 1")
+            .err()
+            .unwrap();
+        assert_eq!((2, 10, "x"), (err.line, err.column, err.text.as_str()));
+
+        let err = parse_input("[A]\n 1 4000000000\n\nmove 1 from 1 to 1")
+            .err()
+            .unwrap();
+        assert_eq!(
+            "line 2, column 4: Expected stack number 2 `4000000000`",
+            err.to_string()
+        );
+
+        let err = Board::from_str("[A]\n 1  2").err().unwrap();
+        assert_eq!((2, 5, "2"), (err.line, err.column, err.text.as_str()));
+
+        let err = Board::from_str("[A] [B] [C]\n 1   2").err().unwrap();
+        assert_eq!(
+            "line 1, column 9: There are only 2 stacks `[C]`",
+            err.to_string()
+        );
+        assert!(Board::from_str("[A] [B] \n 1   2 ").is_ok());
+    }
+
+    #[test]
+    fn execute_errors_test() {
+        let input = "[Z] [M]\n 1   2\n\nmove 1 from 1 to 2\nmove 2 from 1 to 2";
+        let (board, instructions) = parse_input(input).unwrap();
+        let err = part1(&board, &instructions).unwrap_err();
+        assert_eq!("Line 5: Not enough items on stack 1", format!("{:#}", err));
+    }
+
+    #[test]
+    fn solution_test() {
+        let input = EXAMPLE;
+        assert_eq!(
+            vec![(1, Answer::from("CMZ")), (2, Answer::from("MCD"))],
+            Day5.run(input, None).unwrap().answers()
+        );
     }
 }
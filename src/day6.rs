@@ -1,48 +1,76 @@
-use itertools::Itertools;
+use anyhow::{Context, Result};
 
-fn part1(input: &str) -> u32 {
-    let mut unique_since = 0;
+use crate::parse::ParseError;
+use crate::solution::Solution;
 
-    for (i, c) in input.chars().enumerate() {
-        if i - unique_since == 4 {
-            return i as u32;
+/// The datastream on its own, checked to be lowercase ASCII letters so it
+/// can be searched a byte at a time.
+fn parse_signal(input: &str) -> Result<String, ParseError> {
+    let signal = input.trim_end_matches(['\r', '\n']);
+    for (i, line) in signal.split('\n').enumerate() {
+        if i > 0 {
+            return Err(ParseError::new(line, line, "Expected a single line").at_line(i + 1));
         }
-        if let Some((index, _)) = input[unique_since..i]
-            .chars()
-            .find_position(|&new_char| c == new_char)
-        {
-            unique_since += index + 1;
+        if let Some((j, c)) = line.char_indices().find(|(_, c)| !c.is_ascii_lowercase()) {
+            return Err(ParseError::new(
+                line,
+                &line[j..j + c.len_utf8()],
+                "Expected a lowercase letter",
+            )
+            .at_line(i + 1));
         }
     }
-
-    0
+    Ok(signal.to_owned())
 }
 
-fn part2(input: &str) -> u32 {
+/// How many characters have been read once the last `size` are all different.
+fn marker_end(signal: &[u8], size: usize) -> Option<usize> {
     let mut unique_since = 0;
 
-    for (i, c) in input.chars().enumerate() {
-        if i - unique_since == 14 {
-            return i as u32;
+    for (i, &c) in signal.iter().enumerate() {
+        if i - unique_since == size {
+            return Some(i);
         }
-        if let Some((index, _)) = input[unique_since..i]
-            .chars()
-            .find_position(|&new_char| c == new_char)
-        {
+        if let Some(index) = signal[unique_since..i].iter().position(|&seen| seen == c) {
             unique_since += index + 1;
         }
     }
 
-    0
+    (signal.len() - unique_since >= size).then_some(signal.len())
+}
+
+fn find_marker(signal: &str, size: usize) -> Result<u32> {
+    let end = marker_end(signal.as_bytes(), size)
+        .with_context(|| format!("No {} different characters in a row", size))?;
+    Ok(end.try_into()?)
+}
+
+fn part1(signal: &str) -> Result<u32> {
+    find_marker(signal, 4)
 }
 
-pub fn solve() {
-    let input = std::fs::read_to_string("input/day6.txt").expect("Need input");
+fn part2(signal: &str) -> Result<u32> {
+    find_marker(signal, 14)
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = String;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Ok(parse_signal(input)?)
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Part1> {
+        part1(input)
+    }
 
-    let score = part1(&input);
-    println!("[Day  6][Part 1] - {}", score);
-    let score = part2(&input);
-    println!("[Day  6][Part 2] - {}", score);
+    fn part2(&self, input: &Self::Input) -> Result<Self::Part2> {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
@@ -52,15 +80,53 @@ mod tests {
     #[test]
     fn part1_test() {
         let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
-        assert_eq!(7, part1(input));
+        assert_eq!(7, part1(input).unwrap());
 
         let input = "bvwbjplbgvbhsrlpgdmjqwftvncz";
-        assert_eq!(5, part1(input));
+        assert_eq!(5, part1(input).unwrap());
 
         let input = "nppdvjthqldpwncqszvftbrmjlhg";
-        assert_eq!(6, part1(input));
+        assert_eq!(6, part1(input).unwrap());
 
         let input = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
-        assert_eq!(10, part1(input));
+        assert_eq!(10, part1(input).unwrap());
+    }
+
+    #[test]
+    fn part2_test() {
+        assert_eq!(19, part2("mjqjpqmgbljsphdztnvjfqwrcgsmlb").unwrap());
+        assert_eq!(23, part2("bvwbjplbgvbhsrlpgdmjqwftvncz").unwrap());
+    }
+
+    #[test]
+    fn markers_at_the_edges() {
+        assert_eq!(4, part1("abcd").unwrap());
+        assert_eq!(5, part1("aabcd").unwrap());
+
+        let err = part1("aaaabbbb").unwrap_err();
+        assert_eq!("No 4 different characters in a row", err.to_string());
+        assert!(part1("").is_err());
+        assert!(part2("abcd").is_err());
+    }
+
+    #[test]
+    fn parse_errors_test() {
+        assert_eq!("abcd", parse_signal("abcd\n").unwrap());
+        assert_eq!("abcd", parse_signal("abcd\r\n").unwrap());
+
+        let err = parse_signal("ééabcd").unwrap_err();
+        assert_eq!(
+            "line 1, column 1: Expected a lowercase letter `é`",
+            err.to_string()
+        );
+
+        let err = parse_signal("abcD").unwrap_err();
+        assert_eq!((1, 4), (err.line, err.column));
+
+        let err = parse_signal("abcd\nefgh").unwrap_err();
+        assert_eq!(
+            (2, "Expected a single line"),
+            (err.line, err.message.as_str())
+        );
     }
 }
@@ -0,0 +1,174 @@
+use std::io::{BufRead, Cursor};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::answers::{Answers, Status};
+use crate::registry::{self, Entry};
+
+/// A puzzle example stored as `examples/dayN/NAME.txt`, with the answers it
+/// should give in `examples/dayN/NAME.toml`.
+pub struct Example {
+    pub day: u32,
+    pub name: String,
+    pub input: String,
+    pub answers: Answers,
+}
+
+pub fn examples_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("examples")
+}
+
+/// Every example under `dir`, in day and then name order.
+pub fn discover(dir: &Path) -> Result<Vec<Example>> {
+    let mut examples = vec![];
+
+    for day_dir in
+        std::fs::read_dir(dir).with_context(|| format!("Could not read {}", dir.display()))?
+    {
+        let day_dir = day_dir?.path();
+        let Some(day) = day_dir
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_prefix("day")?.parse().ok())
+        else {
+            continue;
+        };
+
+        for file in std::fs::read_dir(&day_dir)? {
+            let path = file?.path();
+            if path.extension().is_none_or(|ext| ext != "txt") {
+                continue;
+            }
+            let name = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .with_context(|| format!("Bad example name {}", path.display()))?
+                .to_owned();
+
+            let answers_path = path.with_extension("toml");
+            let answers = std::fs::read_to_string(&answers_path)
+                .with_context(|| format!("Missing expected answers {}", answers_path.display()))?;
+            let answers = Answers::parse_day(&answers, day)
+                .with_context(|| format!("Invalid answers file {}", answers_path.display()))?;
+
+            examples.push(Example {
+                day,
+                name,
+                input: std::fs::read_to_string(&path)?,
+                answers,
+            });
+        }
+    }
+
+    examples.sort_by(|a, b| (a.day, &a.name).cmp(&(b.day, &b.name)));
+    Ok(examples)
+}
+
+/// Runs `entry` on `example`, along with its strict and streaming versions,
+/// and describes every part that didn't give the expected answer. An example
+/// without any expected answers, like the one `aoc new` writes, checks nothing
+/// and so fails too.
+fn check(entry: &Entry, example: &Example) -> Vec<String> {
+    let label = format!(
+        "day{}/{} with {}",
+        example.day,
+        example.name,
+        entry.variant.unwrap_or("main")
+    );
+
+    let parts: Vec<u32> = (1..=2)
+        .filter(|&part| example.answers.get(example.day, part).is_some())
+        .collect();
+    if parts.is_empty() {
+        return vec![format!("{}: no expected answers to check", label)];
+    }
+
+    let mut failures = vec![];
+    for part in parts {
+        let mut runs = vec![("", entry.solution.run(&example.input, Some(part)))];
+        if let Some(strict) = &entry.strict {
+            runs.push((" strict", strict.run(&example.input, Some(part))));
+        }
+        if let Some(streaming) = &entry.streaming {
+            let mut open = || -> Result<Box<dyn BufRead>> {
+                Ok(Box::new(Cursor::new(example.input.clone().into_bytes())))
+            };
+            runs.push((" streamed", streaming.stream(&mut open, Some(part))));
+        }
+
+        for (how, run) in runs {
+            let run = match run {
+                Ok(run) => run,
+                Err(err) => {
+                    failures.push(format!("{}{} part {}: {:#}", label, how, part, err));
+                    continue;
+                }
+            };
+            let answer = &run.parts[0].answer;
+            match example.answers.check(example.day, part, answer) {
+                Status::Pass => {}
+                status => failures.push(format!(
+                    "{}{} part {}: got {}, {}",
+                    label, how, part, answer, status
+                )),
+            }
+        }
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_solution_solves_its_examples() {
+        let examples = discover(&examples_dir()).unwrap();
+        let entries = registry::registry();
+
+        let mut failures = vec![];
+        for entry in &entries {
+            let mine: Vec<_> = examples.iter().filter(|e| e.day == entry.day).collect();
+            if mine.is_empty() {
+                failures.push(format!("day {} has no examples", entry.day));
+            }
+            for example in mine {
+                failures.extend(check(entry, example));
+            }
+        }
+
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn examples_without_answers_fail() {
+        let entries = registry::registry();
+        let mut example = Example {
+            day: 1,
+            name: "example".to_owned(),
+            input: "1\n2\n\n4\n".to_owned(),
+            answers: Answers::parse_day("# part1 = \n# part2 = \n", 1).unwrap(),
+        };
+        assert_eq!(
+            vec!["day1/example with main: no expected answers to check"],
+            check(&entries[0], &example)
+        );
+
+        example.answers = Answers::parse_day("part1 = 4", 1).unwrap();
+        assert!(check(&entries[0], &example).is_empty());
+    }
+
+    #[test]
+    fn discover_test() {
+        let examples = discover(&examples_dir()).unwrap();
+        let day6: Vec<_> = examples
+            .iter()
+            .filter(|e| e.day == 6)
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(
+            vec!["example1", "example2", "example3", "example4", "example5"],
+            day6
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! Cyclic "beats" games like Rock-Paper-Scissors, with any odd number of moves.
+
+use anyhow::{bail, Result};
+
+/// A move in a [`Game`], numbered by its place in the game's cycle.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
+pub struct Move(pub usize);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+/// A game where every move beats the `(n - 1) / 2` moves before it in the
+/// cycle and loses to the `(n - 1) / 2` after it, wrapping around. With an
+/// odd number of moves every pair of different moves has a winner.
+#[derive(Debug, Clone)]
+pub struct Game {
+    names: Vec<String>,
+    move_scores: Vec<u32>,
+    /// Scores for losing, drawing and winning, in that order.
+    outcome_scores: [u32; 3],
+}
+
+impl Game {
+    /// A game of `moves` in cycle order, each with its name and score.
+    pub fn new(moves: &[(&str, u32)], outcome_scores: [u32; 3]) -> Result<Game> {
+        if moves.len() < 3 || moves.len().is_multiple_of(2) {
+            bail!(
+                "A game needs an odd number of moves, at least 3, not {}",
+                moves.len()
+            );
+        }
+        for (i, (name, _)) in moves.iter().enumerate() {
+            if moves[..i]
+                .iter()
+                .any(|(other, _)| other.eq_ignore_ascii_case(name))
+            {
+                bail!("Move `{}` is in the game twice", name);
+            }
+        }
+        Ok(Game {
+            names: moves.iter().map(|(name, _)| name.to_string()).collect(),
+            move_scores: moves.iter().map(|&(_, score)| score).collect(),
+            outcome_scores,
+        })
+    }
+
+    /// Rock, Paper and Scissors, scored like day 2.
+    pub fn rock_paper_scissors() -> Game {
+        Game::new(&[("rock", 1), ("paper", 2), ("scissors", 3)], [0, 3, 6]).unwrap()
+    }
+
+    fn len(&self) -> usize {
+        self.move_scores.len()
+    }
+
+    /// Every move, in cycle order.
+    pub fn moves(&self) -> impl Iterator<Item = Move> {
+        (0..self.len()).map(Move)
+    }
+
+    pub fn name(&self, mov: Move) -> &str {
+        &self.names[mov.0]
+    }
+
+    /// The move called `name`, ignoring case.
+    pub fn find(&self, name: &str) -> Option<Move> {
+        self.names
+            .iter()
+            .position(|other| other.eq_ignore_ascii_case(name))
+            .map(Move)
+    }
+
+    /// How far `to` is after `from` in the cycle.
+    fn distance(&self, from: Move, to: Move) -> usize {
+        (to.0 + self.len() - from.0) % self.len()
+    }
+
+    pub fn beats(&self, mine: Move, theirs: Move) -> bool {
+        (1..=self.len() / 2).contains(&self.distance(theirs, mine))
+    }
+
+    pub fn outcome(&self, mine: Move, theirs: Move) -> Outcome {
+        if mine == theirs {
+            Outcome::Draw
+        } else if self.beats(mine, theirs) {
+            Outcome::Win
+        } else {
+            Outcome::Lose
+        }
+    }
+
+    /// What playing `mov` is worth, whatever the outcome.
+    pub fn move_score(&self, mov: Move) -> u32 {
+        self.move_scores[mov.0]
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> u32 {
+        self.outcome_scores[outcome as usize]
+    }
+
+    /// The score of the second player in `(theirs, mine)`.
+    pub fn score_round(&self, (theirs, mine): (Move, Move)) -> u32 {
+        self.move_score(mine) + self.outcome_score(self.outcome(mine, theirs))
+    }
+
+    /// A move with the given outcome against `theirs`. When several moves
+    /// would do, this picks the one next to `theirs` in the cycle.
+    pub fn fit_strategy(&self, theirs: Move, outcome: Outcome) -> Move {
+        let offset = match outcome {
+            Outcome::Lose => self.len() - 1,
+            Outcome::Draw => 0,
+            Outcome::Win => 1,
+        };
+        Move((theirs.0 + offset) % self.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROCK: Move = Move(0);
+    const SPOCK: Move = Move(1);
+    const PAPER: Move = Move(2);
+    const LIZARD: Move = Move(3);
+    const SCISSORS: Move = Move(4);
+
+    fn rock_paper_scissors_lizard_spock() -> Game {
+        Game::new(
+            &[
+                ("rock", 1),
+                ("Spock", 2),
+                ("paper", 3),
+                ("lizard", 4),
+                ("scissors", 5),
+            ],
+            [0, 3, 6],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn rock_paper_scissors_test() {
+        let game = Game::rock_paper_scissors();
+        let (rock, paper, scissors) = (Move(0), Move(1), Move(2));
+        assert!(game.beats(paper, rock));
+        assert!(game.beats(scissors, paper));
+        assert!(game.beats(rock, scissors));
+        assert!(!game.beats(rock, paper));
+        assert!(!game.beats(rock, rock));
+
+        assert_eq!(8, game.score_round((rock, paper)));
+        assert_eq!(1, game.score_round((paper, rock)));
+        assert_eq!(6, game.score_round((scissors, scissors)));
+    }
+
+    #[test]
+    fn rock_paper_scissors_lizard_spock_test() {
+        let game = rock_paper_scissors_lizard_spock();
+        let wins = [
+            (SCISSORS, PAPER),
+            (PAPER, ROCK),
+            (ROCK, LIZARD),
+            (LIZARD, SPOCK),
+            (SPOCK, SCISSORS),
+            (SCISSORS, LIZARD),
+            (LIZARD, PAPER),
+            (PAPER, SPOCK),
+            (SPOCK, ROCK),
+            (ROCK, SCISSORS),
+        ];
+        for (winner, loser) in wins {
+            assert_eq!(Outcome::Win, game.outcome(winner, loser));
+            assert_eq!(Outcome::Lose, game.outcome(loser, winner));
+        }
+
+        // every move beats exactly half of the others
+        for mine in game.moves() {
+            let beaten = game.moves().filter(|&theirs| game.beats(mine, theirs));
+            assert_eq!(2, beaten.count());
+        }
+
+        assert_eq!(4 + 6, game.score_round((PAPER, LIZARD)));
+        assert_eq!(3 + 3, game.score_round((PAPER, PAPER)));
+        assert_eq!(3, game.score_round((LIZARD, PAPER)));
+    }
+
+    #[test]
+    fn fit_strategy_test() {
+        let game = rock_paper_scissors_lizard_spock();
+        for theirs in game.moves() {
+            for outcome in [Outcome::Lose, Outcome::Draw, Outcome::Win] {
+                let mine = game.fit_strategy(theirs, outcome);
+                assert_eq!(outcome, game.outcome(mine, theirs));
+            }
+        }
+        assert_eq!(PAPER, game.fit_strategy(SPOCK, Outcome::Win));
+        assert_eq!(ROCK, game.fit_strategy(SPOCK, Outcome::Lose));
+    }
+
+    #[test]
+    fn names() {
+        let game = rock_paper_scissors_lizard_spock();
+        assert_eq!(Some(SPOCK), game.find("spock"));
+        assert_eq!(Some(LIZARD), game.find("Lizard"));
+        assert_eq!(None, game.find("well"));
+        assert_eq!("Spock", game.name(SPOCK));
+    }
+
+    #[test]
+    fn invalid_games() {
+        let game = |names: &[&str]| {
+            let moves: Vec<_> = names.iter().map(|&name| (name, 1)).collect();
+            Game::new(&moves, [0, 3, 6])
+        };
+        assert!(game(&["rock", "paper"]).is_err());
+        assert!(game(&["a", "b", "c", "d"]).is_err());
+        assert!(game(&["rock"]).is_err());
+        assert!(game(&["rock", "paper", "Rock"]).is_err());
+        assert!(game(&["rock", "paper", "scissors"]).is_ok());
+    }
+}
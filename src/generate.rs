@@ -0,0 +1,223 @@
+//! Random puzzle inputs for testing solutions against each other.
+
+use std::io::{BufRead, Read};
+
+use crate::solution::{Answer, StreamRunner};
+
+/// Small xorshift generator so generated inputs are reproducible from a seed.
+#[derive(Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // xorshift gets stuck on zero
+        Rng(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i as u64 + 1) as usize);
+        }
+    }
+}
+
+/// Input produced a line at a time, so tests can stream far more of it than
+/// would comfortably fit in memory.
+pub struct Generated<I> {
+    lines: I,
+    line: Vec<u8>,
+    pos: usize,
+}
+
+impl<I: Iterator<Item = String>> Generated<I> {
+    pub fn new(lines: I) -> Generated<I> {
+        Generated {
+            lines,
+            line: vec![],
+            pos: 0,
+        }
+    }
+}
+
+impl<I: Iterator<Item = String>> Read for Generated<I> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<I: Iterator<Item = String>> BufRead for Generated<I> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.pos == self.line.len() {
+            if let Some(line) = self.lines.next() {
+                self.line.clear();
+                self.line.extend_from_slice(line.as_bytes());
+                self.line.push(b'\n');
+                self.pos = 0;
+            }
+        }
+        Ok(&self.line[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt;
+    }
+}
+
+/// Streams both parts of `solution` over the lines from `lines`, which is
+/// called again for each part.
+pub fn stream_answers<I: Iterator<Item = String> + 'static>(
+    solution: &dyn StreamRunner,
+    lines: impl Fn() -> I,
+) -> Vec<(u32, Answer)> {
+    let mut open = || -> anyhow::Result<Box<dyn BufRead>> { Ok(Box::new(Generated::new(lines()))) };
+    solution.stream(&mut open, None).unwrap().answers()
+}
+
+/// A day 2 strategy guide with `rounds` lines like `A Y`.
+pub fn strategy_guide(rng: &mut Rng, rounds: usize) -> String {
+    (0..rounds)
+        .map(|_| {
+            format!(
+                "{} {}",
+                rng.pick(&['A', 'B', 'C']),
+                rng.pick(&['X', 'Y', 'Z'])
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Day 1 calorie lists for `elves` elves carrying 1 to `max_items` items each.
+pub fn calorie_lines(mut rng: Rng, elves: usize, max_items: u64) -> impl Iterator<Item = String> {
+    (0..elves).flat_map(move |elf| {
+        let items = 1 + rng.below(max_items);
+        let mut lines: Vec<String> = (0..items)
+            .map(|_| (1 + rng.below(9999)).to_string())
+            .collect();
+        if elf + 1 < elves {
+            lines.push(String::new());
+        }
+        lines
+    })
+}
+
+const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Day 3 rucksacks in `groups` groups of three. Every rucksack has exactly one
+/// item in both compartments and every group exactly one badge.
+pub fn rucksack_lines(mut rng: Rng, groups: usize) -> impl Iterator<Item = String> {
+    (0..groups).flat_map(move |_| {
+        let mut letters = ITEMS.to_vec();
+        rng.shuffle(&mut letters);
+        let badge = letters[0];
+
+        // each rucksack draws from its own 17 letters so only the badge is shared
+        letters[1..]
+            .chunks(17)
+            .map(|own| {
+                let size = 2 + rng.below(15) as usize;
+                let mut first: Vec<u8> = (1..size).map(|_| *rng.pick(&own[1..9])).collect();
+                let mut second: Vec<u8> = (2..size).map(|_| *rng.pick(&own[9..])).collect();
+                first.push(own[0]);
+                second.extend([own[0], badge]);
+                rng.shuffle(&mut first);
+                rng.shuffle(&mut second);
+                String::from_utf8([first, second].concat()).unwrap()
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Day 4 section assignment pairs like `2-4,6-8`.
+pub fn section_pairs(mut rng: Rng, pairs: usize) -> impl Iterator<Item = String> {
+    let mut interval = move || {
+        let a = 1 + rng.below(99);
+        let b = 1 + rng.below(99);
+        format!("{}-{}", a.min(b), a.max(b))
+    };
+    (0..pairs).map(move |_| format!("{},{}", interval(), interval()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_output() {
+        assert_eq!(
+            strategy_guide(&mut Rng::new(7), 20),
+            strategy_guide(&mut Rng::new(7), 20)
+        );
+    }
+
+    #[test]
+    fn strategy_guide_test() {
+        let guide = strategy_guide(&mut Rng::new(1), 1000);
+        assert_eq!(1000, guide.lines().count());
+        for line in guide.lines() {
+            let (first, second) = line.split_once(' ').unwrap();
+            assert!(["A", "B", "C"].contains(&first));
+            assert!(["X", "Y", "Z"].contains(&second));
+        }
+
+        // every combination shows up in a guide this long
+        let mut lines: Vec<_> = guide.lines().collect();
+        lines.sort();
+        lines.dedup();
+        assert_eq!(9, lines.len());
+    }
+
+    #[test]
+    fn generated_reads_every_line() {
+        let lines: Vec<String> = section_pairs(Rng::new(3), 100).collect();
+        let mut read = String::new();
+        Generated::new(lines.clone().into_iter())
+            .read_to_string(&mut read)
+            .unwrap();
+        assert_eq!(lines.join("\n") + "\n", read);
+    }
+
+    #[test]
+    fn rucksacks_share_one_item() {
+        let sacks: Vec<String> = rucksack_lines(Rng::new(5), 100).collect();
+        let common = |a: &[u8], b: &[u8]| -> Vec<u8> {
+            let mut common: Vec<u8> = a.iter().filter(|c| b.contains(c)).copied().collect();
+            common.sort();
+            common.dedup();
+            common
+        };
+        for sack in &sacks {
+            let (first, second) = sack.split_at(sack.len() / 2);
+            assert_eq!(
+                1,
+                common(first.as_bytes(), second.as_bytes()).len(),
+                "{}",
+                sack
+            );
+        }
+        for group in sacks.chunks(3) {
+            let shared = common(group[0].as_bytes(), group[1].as_bytes());
+            assert_eq!(1, common(&shared, group[2].as_bytes()).len());
+        }
+    }
+}
@@ -0,0 +1,524 @@
+use std::fmt::{self, Display};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Where the puzzle input for a run comes from.
+#[derive(Debug, PartialEq, Clone)]
+pub enum InputSource {
+    /// The day's input from the cache, fetching it first if needed.
+    Default,
+    File(PathBuf),
+    Stdin,
+    Inline(String),
+}
+
+/// Picks the input directory: the explicit one, then `$AOC_INPUT_DIR`, then `input`.
+pub fn input_dir(explicit: Option<&Path>) -> PathBuf {
+    explicit
+        .map(Path::to_path_buf)
+        .or_else(|| std::env::var_os(INPUT_DIR_VAR).map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from("input"))
+}
+
+pub fn read_file(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("Need to provide puzzle input {}", path.display()))
+}
+
+/// Downloads the puzzle input for a day that isn't cached yet.
+pub trait Fetcher {
+    fn fetch(&self, year: u32, day: u32) -> Result<String>;
+}
+
+impl<F: Fn(u32, u32) -> Result<String>> Fetcher for F {
+    fn fetch(&self, year: u32, day: u32) -> Result<String> {
+        self(year, day)
+    }
+}
+
+/// Fetches inputs over plain HTTP from `{base}/{year}/day/{day}/input`, the
+/// same layout as adventofcode.com. There is no TLS support, so the real site
+/// needs a local proxy in front of it.
+#[derive(Debug, PartialEq)]
+pub struct HttpFetcher {
+    /// `host:port` to connect to.
+    authority: String,
+    /// Path the puzzle paths are appended to, without a trailing `/`.
+    prefix: String,
+    session: Option<String>,
+    /// How long connecting, and each read and write, may take.
+    timeout: Duration,
+}
+
+impl HttpFetcher {
+    pub fn new(base_url: &str) -> Result<HttpFetcher> {
+        let rest = base_url
+            .strip_prefix("http://")
+            .with_context(|| format!("Only http:// URLs can be fetched, got `{}`", base_url))?;
+        let (authority, prefix) = match rest.split_once('/') {
+            Some((authority, path)) => (authority, format!("/{}", path.trim_end_matches('/'))),
+            None => (rest, String::new()),
+        };
+        if authority.is_empty() {
+            bail!("Missing host in `{}`", base_url);
+        }
+
+        let authority = if authority.contains(':') {
+            authority.to_owned()
+        } else {
+            format!("{}:80", authority)
+        };
+        let prefix = if prefix == "/" { String::new() } else { prefix };
+
+        Ok(HttpFetcher {
+            authority,
+            prefix,
+            session: None,
+            timeout: Duration::from_secs(30),
+        })
+    }
+
+    /// Sends `session` as the session cookie, like a logged in browser.
+    pub fn session(mut self, session: Option<String>) -> HttpFetcher {
+        self.session = session;
+        self
+    }
+
+    fn connect(&self) -> Result<TcpStream> {
+        let mut last_err = None;
+        for addr in self.authority.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, self.timeout) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(self.timeout))?;
+                    stream.set_write_timeout(Some(self.timeout))?;
+                    return Ok(stream);
+                }
+                Err(err) => last_err = Some(err),
+            }
+        }
+        match last_err {
+            Some(err) => Err(err.into()),
+            None => bail!("No addresses found"),
+        }
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, year: u32, day: u32) -> Result<String> {
+        let path = format!("{}/{}/day/{}/input", self.prefix, year, day);
+        let url = format!("http://{}{}", self.authority, path);
+
+        let mut stream = self
+            .connect()
+            .with_context(|| format!("Could not connect to {}", self.authority))?;
+        let mut request = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: advent-of-code-2022\r\nConnection: close\r\n",
+            path, self.authority
+        );
+        if let Some(session) = &self.session {
+            request.push_str(&format!("Cookie: session={}\r\n", session));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes())?;
+
+        let mut reader = BufReader::new(stream);
+        let mut head = vec![];
+        loop {
+            let mut line = String::new();
+            reader
+                .read_line(&mut line)
+                .with_context(|| format!("Failed to read response from {}", url))?;
+            if line.is_empty() {
+                bail!("Malformed response from {}", url);
+            }
+            let line = line.trim_end().to_owned();
+            if line.is_empty() {
+                break;
+            }
+            head.push(line);
+        }
+
+        let status_line = head
+            .first()
+            .with_context(|| format!("Malformed response from {}", url))?;
+        if status_line.split(' ').nth(1) != Some("200") {
+            bail!("Fetching {} failed: {}", url, status_line);
+        }
+        let header = |name: &str| {
+            head[1..].iter().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                key.eq_ignore_ascii_case(name).then(|| value.trim())
+            })
+        };
+        if header("Transfer-Encoding").is_some_and(|v| v.eq_ignore_ascii_case("chunked")) {
+            bail!("Chunked responses from {} aren't supported", url);
+        }
+
+        // The server may keep the connection open after the body, so only
+        // read to the end when it doesn't say how long the body is. The body
+        // grows as it arrives rather than trusting the length up front.
+        let length: Option<u64> = header("Content-Length")
+            .map(|length| {
+                length
+                    .parse()
+                    .with_context(|| format!("Malformed Content-Length from {}", url))
+            })
+            .transpose()?;
+        let mut body = vec![];
+        match length {
+            Some(length) => reader.by_ref().take(length).read_to_end(&mut body),
+            None => reader.read_to_end(&mut body),
+        }
+        .with_context(|| format!("Failed to read response from {}", url))?;
+        if let Some(length) = length.filter(|&length| length != body.len() as u64) {
+            bail!(
+                "Response from {} ended after {} of {} bytes",
+                url,
+                body.len(),
+                length
+            );
+        }
+
+        String::from_utf8(body).with_context(|| format!("Response from {} isn't text", url))
+    }
+}
+
+/// The error for a day whose input isn't cached and can't be fetched.
+#[derive(Debug, PartialEq)]
+pub struct MissingInput(pub PathBuf);
+
+impl Display for MissingInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Need to provide puzzle input {}, or pass --fetch URL to download it",
+            self.0.display()
+        )
+    }
+}
+
+impl std::error::Error for MissingInput {}
+
+/// Puzzle inputs cached on disk as `{dir}/{year}/day{day}.txt`, with an
+/// optional fetcher for the ones that are missing.
+pub struct Inputs {
+    dir: PathBuf,
+    fetcher: Option<Box<dyn Fetcher>>,
+}
+
+impl Inputs {
+    pub fn new(dir: impl Into<PathBuf>) -> Inputs {
+        Inputs {
+            dir: dir.into(),
+            fetcher: None,
+        }
+    }
+
+    pub fn fetcher(mut self, fetcher: impl Fetcher + 'static) -> Inputs {
+        self.fetcher = Some(Box::new(fetcher));
+        self
+    }
+
+    pub fn path(&self, year: u32, day: u32) -> PathBuf {
+        self.dir
+            .join(year.to_string())
+            .join(format!("day{}.txt", day))
+    }
+
+    /// Makes sure the input is cached, fetching it if needed, and returns its path.
+    pub fn cached(&self, year: u32, day: u32) -> Result<PathBuf> {
+        let path = self.path(year, day);
+        // An empty file is the placeholder left by `aoc new`, not a real input.
+        if path.metadata().is_ok_and(|m| m.len() > 0) {
+            return Ok(path);
+        }
+
+        let Some(fetcher) = &self.fetcher else {
+            return Err(MissingInput(path).into());
+        };
+        let input = fetcher
+            .fetch(year, day)
+            .with_context(|| format!("Could not fetch input for {} day {}", year, day))?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Written next to the input and then moved into place, so an
+        // interrupted write never leaves a partial input behind.
+        let partial = path.with_extension("txt.tmp");
+        std::fs::write(&partial, &input)
+            .and_then(|()| std::fs::rename(&partial, &path))
+            .inspect_err(|_| {
+                let _ = std::fs::remove_file(&partial);
+            })
+            .with_context(|| format!("Could not cache input in {}", path.display()))?;
+
+        Ok(path)
+    }
+
+    pub fn get(&self, year: u32, day: u32) -> Result<String> {
+        read_file(&self.cached(year, day)?)
+    }
+}
+
+impl InputSource {
+    pub fn load(&self, inputs: &Inputs, year: u32, day: u32) -> Result<String> {
+        match self {
+            InputSource::Default => inputs.get(year, day),
+            InputSource::File(path) => read_file(path),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .context("Failed to read puzzle input from stdin")?;
+                Ok(input)
+            }
+            InputSource::Inline(input) => Ok(input.clone()),
+        }
+    }
+
+    /// Opens the input for reading a line at a time instead of loading it.
+    pub fn open(&self, inputs: &Inputs, year: u32, day: u32) -> Result<Box<dyn BufRead>> {
+        let open = |path: &Path| -> Result<Box<dyn BufRead>> {
+            let file = File::open(path)
+                .with_context(|| format!("Need to provide puzzle input {}", path.display()))?;
+            Ok(Box::new(BufReader::new(file)))
+        };
+        match self {
+            InputSource::Default => open(&inputs.cached(year, day)?),
+            InputSource::File(path) => open(path),
+            InputSource::Stdin => Ok(Box::new(std::io::stdin().lock())),
+            InputSource::Inline(input) => Ok(Box::new(Cursor::new(input.clone().into_bytes()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Stands in for the puzzle server: answers every request with `status`
+    /// and `body`, and records the request lines it saw.
+    fn stand_in(status: &'static str, body: &'static str) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/aoc", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(vec![]));
+
+        let seen = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = std::io::BufReader::new(stream.try_clone().unwrap());
+                let mut lines = vec![];
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    lines.push(line.trim().to_owned());
+                }
+                seen.lock().unwrap().push(lines.join("\n"));
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    #[test]
+    fn load_test() {
+        let dir = temp_dir("aoc-input-load-test");
+        std::fs::create_dir_all(dir.join("2022")).unwrap();
+        std::fs::write(dir.join("2022/day9.txt"), "R 4\nU 4").unwrap();
+        let inputs = Inputs::new(&dir);
+
+        assert_eq!(
+            "R 4\nU 4",
+            InputSource::Default.load(&inputs, 2022, 9).unwrap()
+        );
+        assert_eq!(
+            "R 4\nU 4",
+            InputSource::File(dir.join("2022/day9.txt"))
+                .load(&Inputs::new("elsewhere"), 2022, 9)
+                .unwrap()
+        );
+        assert_eq!(
+            "L 1",
+            InputSource::Inline("L 1".to_owned())
+                .load(&inputs, 2022, 9)
+                .unwrap()
+        );
+        assert!(InputSource::Default.load(&inputs, 2022, 10).is_err());
+    }
+
+    #[test]
+    fn open_test() {
+        let dir = temp_dir("aoc-input-open-test");
+        std::fs::create_dir_all(dir.join("2022")).unwrap();
+        std::fs::write(dir.join("2022/day9.txt"), "R 4\nU 4").unwrap();
+        let inputs = Inputs::new(&dir);
+
+        let lines = |source: InputSource| -> Vec<String> {
+            let input = source.open(&inputs, 2022, 9).unwrap();
+            input.lines().map(Result::unwrap).collect()
+        };
+        assert_eq!(vec!["R 4", "U 4"], lines(InputSource::Default));
+        assert_eq!(
+            vec!["R 4", "U 4"],
+            lines(InputSource::File(dir.join("2022/day9.txt")))
+        );
+        assert_eq!(vec!["L 1"], lines(InputSource::Inline("L 1".to_owned())));
+        assert!(InputSource::Default.open(&inputs, 2022, 10).is_err());
+    }
+
+    #[test]
+    fn explicit_input_dir_wins() {
+        assert_eq!(PathBuf::from("alice"), input_dir(Some(Path::new("alice"))));
+    }
+
+    #[test]
+    fn fetches_and_caches() {
+        let dir = temp_dir("aoc-input-fetch-test");
+        let inputs = Inputs::new(&dir).fetcher(|year, day| Ok(format!("{} {}", year, day)));
+
+        assert_eq!("2022 7", inputs.get(2022, 7).unwrap());
+        assert_eq!(
+            "2022 7",
+            std::fs::read_to_string(dir.join("2022/day7.txt")).unwrap()
+        );
+        assert!(!dir.join("2022/day7.txt.tmp").exists());
+
+        let offline = Inputs::new(&dir);
+        assert_eq!("2022 7", offline.get(2022, 7).unwrap());
+        let err = offline.get(2022, 8).unwrap_err();
+        assert_eq!(
+            Some(&MissingInput(dir.join("2022/day8.txt"))),
+            err.downcast_ref()
+        );
+
+        std::fs::write(dir.join("2022/day8.txt"), "").unwrap();
+        assert!(offline.get(2022, 8).is_err());
+        assert_eq!("2022 8", inputs.get(2022, 8).unwrap());
+    }
+
+    #[test]
+    fn http_fetcher() {
+        let (url, requests) = stand_in("200 OK", "1-2,3-4\n");
+        let fetcher = HttpFetcher::new(&url)
+            .unwrap()
+            .session(Some("abc".to_owned()));
+
+        let dir = temp_dir("aoc-input-http-test");
+        let inputs = Inputs::new(&dir).fetcher(fetcher);
+        assert_eq!("1-2,3-4\n", inputs.get(2022, 4).unwrap());
+        assert_eq!("1-2,3-4\n", inputs.get(2022, 4).unwrap());
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(1, requests.len(), "second read should hit the cache");
+        assert!(requests[0].starts_with("GET /aoc/2022/day/4/input HTTP/1.1"));
+        assert!(requests[0].contains("Cookie: session=abc"));
+    }
+
+    #[test]
+    fn http_fetcher_errors() {
+        let (url, _) = stand_in("404 Not Found", "");
+        let dir = temp_dir("aoc-input-http-error-test");
+        let inputs = Inputs::new(&dir).fetcher(HttpFetcher::new(&url).unwrap());
+        assert!(inputs.get(2022, 30).is_err());
+        assert!(!inputs.path(2022, 30).exists());
+
+        assert!(HttpFetcher::new("https://adventofcode.com").is_err());
+        assert!(HttpFetcher::new("http://").is_err());
+    }
+
+    #[test]
+    fn http_fetcher_short_body() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request).unwrap();
+                // claims far more than it sends, then hangs up
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: 999999999999\r\n\r\n1-2"
+                )
+                .unwrap();
+            }
+        });
+
+        let dir = temp_dir("aoc-input-http-short-test");
+        let inputs = Inputs::new(&dir).fetcher(HttpFetcher::new(&url).unwrap());
+        let err = inputs.get(2022, 4).unwrap_err();
+        assert!(format!("{:#}", err).contains("ended after 3 of 999999999999 bytes"));
+        assert!(!inputs.path(2022, 4).exists());
+    }
+
+    #[test]
+    fn http_fetcher_stops_waiting() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let mut open = vec![];
+            for (i, stream) in listener.incoming().enumerate() {
+                let mut stream = stream.unwrap();
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request).unwrap();
+                // the first request never gets an answer, the second gets
+                // its body but the connection stays open
+                if i > 0 {
+                    write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: 3\r\n\r\n1-2").unwrap();
+                }
+                open.push(stream);
+            }
+        });
+
+        let fetcher = HttpFetcher {
+            timeout: Duration::from_millis(200),
+            ..HttpFetcher::new(&url).unwrap()
+        };
+        let err = fetcher.fetch(2022, 4).unwrap_err();
+        assert!(format!("{:#}", err).contains("Failed to read response"));
+        assert_eq!("1-2", fetcher.fetch(2022, 4).unwrap());
+    }
+
+    #[test]
+    fn http_fetcher_urls() {
+        let fetcher = HttpFetcher::new("http://localhost").unwrap();
+        assert_eq!("localhost:80", fetcher.authority);
+        assert_eq!("", fetcher.prefix);
+
+        let fetcher = HttpFetcher::new("http://127.0.0.1:8080/proxy/").unwrap();
+        assert_eq!("127.0.0.1:8080", fetcher.authority);
+        assert_eq!("/proxy", fetcher.prefix);
+    }
+}
@@ -1,21 +1,227 @@
+mod analysis;
+mod answers;
+mod cli;
 mod day1;
 mod day2;
+mod day2_better;
 mod day3;
 mod day4;
 mod day5;
 mod day6;
+#[cfg(test)]
+mod examples;
+mod game;
+#[cfg(test)]
+mod generate;
+mod input;
+mod parse;
+mod registry;
+mod runner;
+mod scaffold;
+mod solution;
+mod tournament;
 
-fn main() {
-    day1::part1();
-    day1::part2();
+use std::path::Path;
+
+use answers::Answers;
+use anyhow::{bail, Context, Result};
+use cli::{Command, Format};
+use game::Game;
+
+fn run() -> Result<()> {
+    let args = match cli::parse_args(std::env::args().skip(1))? {
+        Command::Run(args) => *args,
+        Command::New(day) => {
+            for path in scaffold::new_day(Path::new("."), day)? {
+                println!("Wrote {}", path.display());
+            }
+            return Ok(());
+        }
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+    };
+
+    let mut entries: Vec<_> = registry::registry()
+        .into_iter()
+        .filter(|entry| args.days.contains(&entry.day))
+        .filter(|entry| !args.stream || entry.streaming.is_some())
+        .collect();
+    if entries.is_empty() && args.stream {
+        bail!("No streaming solutions for days {:?}", args.days);
+    }
+    if entries.is_empty() {
+        bail!("No solutions for days {:?}", args.days);
+    }
+
+    if let Some(width) = args.width {
+        registry::use_day1_width(&mut entries, width);
+    }
+
+    if let Some(size) = args.group_size {
+        registry::use_day3_group_size(&mut entries, size)?;
+    }
+
+    let symbols = match &args.symbols {
+        Some(path) => {
+            let symbols = day2::Symbols::load(path)?;
+            registry::use_day2_symbols(&mut entries, &symbols);
+            symbols
+        }
+        None => day2::Symbols::default(),
+    };
+
+    if let Some(score) = args.find_symbols {
+        let guide = runner::load_input(&entries[0], &args)?;
+        let found = day2::find_symbols(&guide, &symbols, score).context("Day 2 failed")?;
+        if found.is_empty() {
+            bail!("No way of reading the guide scores {}", score);
+        }
+        let game = Game::rock_paper_scissors();
+        let found: Vec<_> = found
+            .iter()
+            .enumerate()
+            .map(|(i, symbols)| {
+                format!("# {} of {}\n{}", i + 1, found.len(), symbols.format(&game))
+            })
+            .collect();
+        print!("{}", found.join("\n"));
+        return Ok(());
+    }
+
+    if let Some(rounds) = args.tournament {
+        let guide = runner::load_input(&entries[0], &args)?;
+        let guide = day2::parse_guide(&guide, &symbols).context("Day 2 failed")?;
+        let mut players = tournament::bots();
+        for (name, moves) in [
+            ("elf", guide.iter().map(|&(theirs, _)| theirs).collect()),
+            ("guide", guide.iter().map(|&(_, mine)| mine).collect()),
+        ] {
+            players.push(Box::new(tournament::Replay {
+                name: name.to_owned(),
+                moves,
+            }));
+        }
+        let game = Game::rock_paper_scissors();
+        println!("{}", tournament::tournament(&game, &mut players, rounds));
+        return Ok(());
+    }
 
-    day2::solve();
+    if args.explain {
+        let guide = runner::load_input(&entries[0], &args)?;
+        let game = Game::rock_paper_scissors();
+        let parts = args
+            .part
+            .map_or(vec![1, 2], |part| vec![part])
+            .into_iter()
+            .map(|part| Ok((part, day2::explain(&game, &guide, &symbols, part)?)))
+            .collect::<Result<Vec<_>>>()
+            .context("Day 2 failed")?;
+        match args.format {
+            Format::Csv => print!("{}", day2::explain_csv(&game, &parts)),
+            _ => {
+                let tables: Vec<String> = parts
+                    .iter()
+                    .map(|(part, steps)| {
+                        format!(
+                            "[Day  2][Part {}]\n{}",
+                            part,
+                            day2::explain_table(&game, steps)
+                        )
+                    })
+                    .collect();
+                println!("{}", tables.join("\n\n"));
+            }
+        }
+        return Ok(());
+    }
 
-    day3::solve();
+    if let Some(runs) = args.bench {
+        let (results, skipped) = runner::bench(&entries, &args, runs)?;
+        for result in results {
+            println!("{}", result.format());
+        }
+        for skipped in skipped {
+            println!("{}", skipped.format());
+        }
+        return Ok(());
+    }
 
-    day4::solve();
+    if args.stats {
+        let (reports, skipped) = runner::stats(&entries, &args)?;
+        if reports.is_empty() {
+            bail!("No statistics for days {:?}", args.days);
+        }
+        for (day, report) in reports {
+            println!("[Day {:>2}] Stats", day);
+            for line in report.lines() {
+                println!("  {}", line);
+            }
+        }
+        for skipped in skipped {
+            println!("{}", skipped.format());
+        }
+        return Ok(());
+    }
 
-    day5::solve();
+    let (mut results, skipped) = if args.stream {
+        runner::stream(&entries, &args)?
+    } else {
+        runner::run(&entries, &args)?
+    };
 
-    day6::solve();
+    let mut failed = 0;
+    if args.verify {
+        let path = args
+            .answers
+            .clone()
+            .unwrap_or_else(|| input::input_dir(args.input_dir.as_deref()).join("answers.toml"));
+        failed = runner::verify(&mut results, &Answers::load(&path)?);
+    }
+
+    let comparisons = runner::compare(&results);
+
+    match args.format {
+        Format::Text => {
+            // skipped days go where their answers would have been
+            let mut lines: Vec<(u32, String)> = results
+                .iter()
+                .map(|result| (result.day, result.format()))
+                .chain(skipped.iter().map(|s| (s.day, s.format())))
+                .collect();
+            lines.sort_by_key(|&(day, _)| day);
+            for (_, line) in lines {
+                println!("{}", line);
+            }
+            for comparison in &comparisons {
+                println!("{}", comparison.format());
+            }
+            if args.verify {
+                println!("{}", runner::summary(&results, &skipped));
+            }
+        }
+        Format::Json => println!("{}", runner::to_json(&results, &skipped)),
+        Format::Csv => unreachable!("only --explain prints CSV"),
+    }
+
+    if failed > 0 {
+        bail!("{} answer(s) did not match", failed);
+    }
+
+    let disagreements = comparisons.iter().filter(|c| !c.agree()).count();
+    if disagreements > 0 {
+        bail!("Variants disagree on {} part(s)", disagreements);
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(err) = run() {
+        // `{:#}` keeps the whole chain on one line, e.g.
+        // Day 2 failed: line 2, column 3: Invalid move `Q`
+        eprintln!("Error: {:#}", err);
+        std::process::exit(1);
+    }
 }
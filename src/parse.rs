@@ -0,0 +1,187 @@
+use std::fmt::{self, Display};
+use std::io::BufRead;
+
+use anyhow::{Context, Result};
+
+/// A problem with the puzzle input, pointing at the offending text.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError {
+    /// 1-based line number, `0` until the caller knows which line it was.
+    pub line: usize,
+    /// 1-based column of `text` within the line.
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error about `token`, which should be a slice of `line`.
+    pub fn new(line: &str, token: &str, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: 0,
+            column: offset(line, token) + 1,
+            text: token.to_owned(),
+            message: message.into(),
+        }
+    }
+
+    /// An error about something missing from the end of `line`.
+    pub fn missing(line: &str, message: impl Into<String>) -> ParseError {
+        ParseError::new(line, &line[line.len()..], message)
+    }
+
+    pub fn at_line(mut self, line: usize) -> ParseError {
+        self.line = line;
+        self
+    }
+
+    /// Moves an error found while parsing `token` so it is relative to `line` instead.
+    pub fn within(mut self, line: &str, token: &str) -> ParseError {
+        self.column += offset(line, token);
+        self
+    }
+}
+
+/// Byte offset of `token` inside `line`, or 0 when it isn't a slice of it.
+fn offset(line: &str, token: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let token_start = token.as_ptr() as usize;
+    if token_start >= start && token_start + token.len() <= start + line.len() {
+        token_start - start
+    } else {
+        0
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        // Nothing to quote when the error is about something missing.
+        if !self.text.is_empty() {
+            write!(f, " `{}`", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses every line of `input` with `f`, filling in the line number of any error.
+pub fn parse_lines<'a, T>(
+    input: &'a str,
+    mut f: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| f(line).map_err(|e| e.at_line(i + 1)))
+        .collect()
+}
+
+/// Calls `f` with every line of `input` in turn, holding only the current
+/// line in memory. Errors get the line number they happened on.
+pub fn for_each_line(input: &mut dyn BufRead, mut f: impl FnMut(&str) -> Result<()>) -> Result<()> {
+    for_each_raw_line(input, |line| f(line.strip_suffix('\r').unwrap_or(line)))
+}
+
+/// Like [`for_each_line`], but leaves the `\r` of `\r\n` line endings on the line.
+pub fn for_each_raw_line(
+    input: &mut dyn BufRead,
+    mut f: impl FnMut(&str) -> Result<()>,
+) -> Result<()> {
+    let mut buffer = String::new();
+    let mut line_no = 0;
+    loop {
+        buffer.clear();
+        if input
+            .read_line(&mut buffer)
+            .with_context(|| format!("Failed to read line {}", line_no + 1))?
+            == 0
+        {
+            return Ok(());
+        }
+        line_no += 1;
+
+        let line = buffer.strip_suffix('\n').unwrap_or(&buffer);
+        f(line).map_err(|err| match err.downcast::<ParseError>() {
+            Ok(err) => err.at_line(line_no).into(),
+            Err(err) => err.context(format!("Line {}", line_no)),
+        })?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns() {
+        let line = "A Q";
+        let err = ParseError::new(line, &line[2..], "Invalid move");
+        assert_eq!(3, err.column);
+        assert_eq!("Q", err.text);
+
+        let err = ParseError::missing(line, "Missing strategy");
+        assert_eq!(4, err.column);
+        assert_eq!("", err.text);
+        assert_eq!("line 0, column 4: Missing strategy", err.to_string());
+
+        let token = &line[2..];
+        let err = ParseError::new(token, token, "Invalid move").within(line, token);
+        assert_eq!(3, err.column);
+    }
+
+    #[test]
+    fn parse_lines_test() {
+        let parse = |line: &str| {
+            line.parse::<u32>()
+                .map_err(|_| ParseError::new(line, line, "Not a number"))
+        };
+        assert_eq!(Ok(vec![1, 2]), parse_lines("1\n2", parse));
+
+        let err = parse_lines("1\n2\n3x", parse).unwrap_err();
+        assert_eq!(3, err.line);
+        assert_eq!("line 3, column 1: Not a number `3x`", err.to_string());
+    }
+
+    #[test]
+    fn for_each_line_test() {
+        let mut lines = vec![];
+        for_each_line(&mut "1\r\n\n2\n".as_bytes(), |line| {
+            lines.push(line.to_owned());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(vec!["1", "", "2"], lines);
+
+        lines.clear();
+        for_each_raw_line(&mut "1\r\n\n2".as_bytes(), |line| {
+            lines.push(line.to_owned());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(vec!["1\r", "", "2"], lines);
+
+        let err = for_each_line(&mut "1\n2\n3x".as_bytes(), |line| {
+            line.parse::<u32>()
+                .map_err(|_| ParseError::new(line, line, "Not a number"))?;
+            Ok(())
+        })
+        .unwrap_err();
+        assert_eq!(
+            "line 3, column 1: Not a number `3x`",
+            err.downcast::<ParseError>().unwrap().to_string()
+        );
+
+        let err = for_each_line(&mut "1\n2".as_bytes(), |line| {
+            anyhow::ensure!(line != "2", "No twos");
+            Ok(())
+        })
+        .unwrap_err();
+        assert_eq!("Line 2: No twos", format!("{:#}", err));
+    }
+}
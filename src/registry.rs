@@ -0,0 +1,145 @@
+use anyhow::Result;
+
+use num::BigUint;
+
+use crate::solution::{Runner, StreamRunner};
+use crate::{day1, day2, day2_better, day3, day4, day5, day6};
+
+pub const YEAR: u32 = 2022;
+
+pub struct Entry {
+    pub year: u32,
+    pub day: u32,
+    /// Name of an alternative implementation, `None` for the main one.
+    pub variant: Option<&'static str>,
+    pub solution: Box<dyn Runner>,
+    /// The same solution rejecting sloppy input for `--strict`, for days
+    /// that normally clean it up.
+    pub strict: Option<Box<dyn Runner>>,
+    /// The same solution reading its input line by line, for days that can.
+    pub streaming: Option<Box<dyn StreamRunner>>,
+}
+
+impl Entry {
+    fn new(day: u32, solution: impl Runner + 'static) -> Entry {
+        Entry {
+            year: YEAR,
+            day,
+            variant: None,
+            solution: Box::new(solution),
+            strict: None,
+            streaming: None,
+        }
+    }
+
+    fn variant(mut self, name: &'static str) -> Entry {
+        self.variant = Some(name);
+        self
+    }
+
+    fn strict(mut self, solution: impl Runner + 'static) -> Entry {
+        self.strict = Some(Box::new(solution));
+        self
+    }
+
+    fn streaming(mut self, solution: impl StreamRunner + 'static) -> Entry {
+        self.streaming = Some(Box::new(solution));
+        self
+    }
+
+    /// The solution to run, the strict one when asked for and there is one.
+    pub fn runner(&self, strict: bool) -> &dyn Runner {
+        match &self.strict {
+            Some(solution) if strict => solution.as_ref(),
+            _ => self.solution.as_ref(),
+        }
+    }
+}
+
+/// Every solution in day order. Alternative implementations of a day are
+/// registered with [`Entry::variant`] right after the main one, and the
+/// runner checks that they all agree. Days that can also read their input
+/// line by line register that with [`Entry::streaming`], and days that clean
+/// up sloppy input register a version that doesn't with [`Entry::strict`].
+pub fn registry() -> Vec<Entry> {
+    vec![
+        day1_entry::<u64>(),
+        Entry::new(2, day2::Day2::default()),
+        Entry::new(2, day2_better::Day2Better).variant("better"),
+        Entry::new(3, day3::Day3::default()).streaming(day3::Day3::default()),
+        Entry::new(4, day4::Day4).streaming(day4::Day4),
+        Entry::new(5, day5::Day5),
+        Entry::new(6, day6::Day6),
+    ]
+}
+
+fn day1_entry<C: day1::Calories + 'static>() -> Entry {
+    Entry::new(1, day1::Day1::<C>::new(day1::Mode::Lenient))
+        .strict(day1::Day1::<C>::new(day1::Mode::Strict))
+        .streaming(day1::Day1::<C>::new(day1::Mode::Lenient))
+}
+
+/// Counts day 1 calories in a `width` type.
+pub fn use_day1_width(entries: &mut [Entry], width: day1::Width) {
+    for entry in entries.iter_mut().filter(|entry| entry.day == 1) {
+        *entry = match width {
+            day1::Width::U64 => day1_entry::<u64>(),
+            day1::Width::U128 => day1_entry::<u128>(),
+            day1::Width::Big => day1_entry::<BigUint>(),
+        };
+    }
+}
+
+/// Reads day 2 strategy guides with `symbols`. The variants of day 2 only
+/// know the usual letters, so they are left out.
+pub fn use_day2_symbols(entries: &mut Vec<Entry>, symbols: &day2::Symbols) {
+    entries.retain(|entry| entry.day != 2 || entry.variant.is_none());
+    for entry in entries.iter_mut().filter(|entry| entry.day == 2) {
+        entry.solution = Box::new(day2::Day2::new(symbols.clone()));
+    }
+}
+
+/// Splits day 3 rucksacks into groups of `size`.
+pub fn use_day3_group_size(entries: &mut [Entry], size: usize) -> Result<()> {
+    for entry in entries.iter_mut().filter(|entry| entry.day == 3) {
+        entry.solution = Box::new(day3::Day3::new(size)?);
+        entry.streaming = Some(Box::new(day3::Day3::new(size)?));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Answer;
+
+    #[test]
+    fn one_main_entry_per_day() {
+        let entries = registry();
+        for day in 1..=6 {
+            let main = entries
+                .iter()
+                .filter(|e| e.day == day && e.variant.is_none())
+                .count();
+            assert_eq!(1, main, "day {}", day);
+        }
+    }
+
+    #[test]
+    fn day1_width() {
+        let input = "18446744073709551615\n\n1\n2\n";
+        let mut entries = registry();
+        assert!(entries[0].solution.run(input, Some(2)).is_err());
+
+        use_day1_width(&mut entries, day1::Width::Big);
+        assert_eq!(7, entries.len());
+        let run = entries[0].runner(true).run(input, None).unwrap();
+        assert_eq!(
+            vec![
+                (1, Answer::number("18446744073709551615")),
+                (2, Answer::number("18446744073709551618")),
+            ],
+            run.answers()
+        );
+    }
+}
@@ -0,0 +1,704 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+
+use crate::answers::{Answers, Status};
+use crate::cli::Args;
+use crate::input::{self, HttpFetcher, InputSource, Inputs, MissingInput};
+use crate::registry::Entry;
+use crate::solution::{Answer, Run};
+
+#[derive(Debug, PartialEq)]
+pub struct PartResult {
+    pub day: u32,
+    pub variant: Option<&'static str>,
+    pub part: u32,
+    pub answer: Answer,
+    /// Time spent parsing the input, shared by every part of a run.
+    pub parse: Duration,
+    pub elapsed: Duration,
+    pub status: Option<Status>,
+    /// What the solution pointed out about the input, shared by every part of a run.
+    pub notes: Vec<String>,
+}
+
+impl PartResult {
+    pub fn format(&self) -> String {
+        let mut line = format!(
+            "[Day {:>2}][Part {}] - {}",
+            self.day, self.part, self.answer
+        );
+        if let Some(variant) = self.variant {
+            line.push_str(&format!(" ({})", variant));
+        }
+        line.push_str(&format!(
+            " [parse {:.2?}, part {:.2?}]",
+            self.parse, self.elapsed
+        ));
+        if let Some(status) = &self.status {
+            line.push_str(&format!(" [{}]", status));
+        }
+        for note in &self.notes {
+            line.push_str(&format!(" [{}]", note));
+        }
+        line
+    }
+}
+
+/// A day left out of a run over several days because its input isn't there.
+#[derive(Debug, PartialEq)]
+pub struct Skipped {
+    pub day: u32,
+    pub reason: String,
+}
+
+impl Skipped {
+    pub fn format(&self) -> String {
+        format!("[Day {:>2}] skipped: {}", self.day, self.reason)
+    }
+
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"day\":{},\"variant\":null,\"part\":null,\"answer\":null,\"parse_ns\":null,\"elapsed_ns\":null,\"status\":\"skipped\",\"notes\":[{}]}}",
+            self.day,
+            json_string(&self.reason)
+        )
+    }
+}
+
+/// The results for every day that was run, and the days that were skipped.
+pub type Results<T> = (Vec<T>, Vec<Skipped>);
+
+/// Turns a missing default input into a skipped day when running several
+/// days, so the others still get answers. Anything else is passed on.
+fn skip_missing(
+    entry: &Entry,
+    args: &Args,
+    err: anyhow::Error,
+    skipped: &mut Vec<Skipped>,
+) -> Result<()> {
+    let several_days = args.days.start() < args.days.end();
+    match err.downcast_ref::<MissingInput>() {
+        Some(missing) if several_days && args.input == InputSource::Default => {
+            skipped.push(Skipped {
+                day: entry.day,
+                reason: missing.to_string(),
+            });
+            Ok(())
+        }
+        _ => Err(err),
+    }
+}
+
+/// Fails when every day was skipped, since then there is nothing to show.
+fn check_skipped(entries: &[Entry], skipped: &[Skipped]) -> Result<()> {
+    let days = entries.iter().map(|entry| entry.day).dedup().count();
+    if days > 0 && skipped.len() == days {
+        bail!("None of the days have an input: {}", skipped[0].reason);
+    }
+    Ok(())
+}
+
+fn part_results(entry: &Entry, run: Run) -> Vec<PartResult> {
+    run.parts
+        .into_iter()
+        .map(|p| PartResult {
+            day: entry.day,
+            variant: entry.variant,
+            part: p.part,
+            answer: p.answer,
+            parse: run.parse,
+            elapsed: p.elapsed,
+            status: None,
+            notes: run.notes.clone(),
+        })
+        .collect()
+}
+
+pub fn run_entry(entry: &Entry, input: &str, args: &Args) -> Result<Vec<PartResult>> {
+    let run = entry
+        .runner(args.strict)
+        .run(input, args.part)
+        .with_context(|| format!("Day {} failed", entry.day))?;
+
+    Ok(part_results(entry, run))
+}
+
+fn inputs(args: &Args) -> Result<Inputs> {
+    let mut inputs = Inputs::new(input::input_dir(args.input_dir.as_deref()));
+    if let Some(url) = &args.fetch {
+        let session = std::env::var(input::SESSION_VAR).ok();
+        inputs = inputs.fetcher(HttpFetcher::new(url)?.session(session));
+    }
+    Ok(inputs)
+}
+
+pub fn load_input(entry: &Entry, args: &Args) -> Result<String> {
+    args.input.load(&inputs(args)?, entry.year, entry.day)
+}
+
+/// Loads the input for every entry and hands both to `f`, returning the days
+/// that were skipped for having no input.
+fn with_inputs(
+    entries: &[Entry],
+    args: &Args,
+    mut f: impl FnMut(&Entry, &str) -> Result<()>,
+) -> Result<Vec<Skipped>> {
+    let inputs = inputs(args)?;
+    let mut skipped = vec![];
+
+    // Variants of the same day are next to each other and share one input,
+    // which matters when it can only be read once from stdin.
+    let mut loaded: Option<(u32, Option<String>)> = None;
+    for entry in entries {
+        let input = match loaded {
+            Some((day, ref input)) if day == entry.day => input,
+            _ => {
+                let input = match args.input.load(&inputs, entry.year, entry.day) {
+                    Ok(input) => Some(input),
+                    Err(err) => {
+                        skip_missing(entry, args, err, &mut skipped)?;
+                        None
+                    }
+                };
+                &loaded.insert((entry.day, input)).1
+            }
+        };
+        if let Some(input) = input {
+            f(entry, input)?;
+        }
+    }
+
+    check_skipped(entries, &skipped)?;
+    Ok(skipped)
+}
+
+pub fn run(entries: &[Entry], args: &Args) -> Result<Results<PartResult>> {
+    let mut results = vec![];
+    let skipped = with_inputs(entries, args, |entry, input| {
+        results.extend(run_entry(entry, input, args)?);
+        Ok(())
+    })?;
+    Ok((results, skipped))
+}
+
+/// Reports on the input of every entry that has a report, as `(day, report)`.
+pub fn stats(entries: &[Entry], args: &Args) -> Result<Results<(u32, String)>> {
+    let mut reports = vec![];
+    let skipped = with_inputs(entries, args, |entry, input| {
+        let report = entry
+            .runner(args.strict)
+            .stats(input)
+            .with_context(|| format!("Day {} failed", entry.day))?;
+        reports.extend(report.map(|report| (entry.day, report)));
+        Ok(())
+    })?;
+    Ok((reports, skipped))
+}
+
+/// Runs the streaming version of every entry that has one, reading the input
+/// a line at a time instead of loading it.
+pub fn stream(entries: &[Entry], args: &Args) -> Result<Results<PartResult>> {
+    let inputs = inputs(args)?;
+    let mut results = vec![];
+    let mut skipped = vec![];
+    for entry in entries {
+        let Some(streaming) = &entry.streaming else {
+            continue;
+        };
+        let mut open = || args.input.open(&inputs, entry.year, entry.day);
+        match streaming.stream(&mut open, args.part) {
+            Ok(run) => results.extend(part_results(entry, run)),
+            Err(err) => skip_missing(entry, args, err, &mut skipped)
+                .with_context(|| format!("Day {} failed", entry.day))?,
+        }
+    }
+    check_skipped(entries, &skipped)?;
+    Ok((results, skipped))
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn new(samples: &mut [Duration]) -> Stats {
+        samples.sort();
+        Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchResult {
+    pub day: u32,
+    pub variant: Option<&'static str>,
+    /// The part that was timed, `None` for parsing.
+    pub part: Option<u32>,
+    pub runs: usize,
+    pub stats: Stats,
+}
+
+impl BenchResult {
+    pub fn format(&self) -> String {
+        let step = match self.part {
+            Some(part) => format!("Part {}", part),
+            None => "Parse ".to_owned(),
+        };
+        let mut line = format!("[Day {:>2}][{}]", self.day, step);
+        if let Some(variant) = self.variant {
+            line.push_str(&format!(" ({})", variant));
+        }
+        line.push_str(&format!(
+            " min {:.2?}, median {:.2?}, max {:.2?} over {} runs",
+            self.stats.min, self.stats.median, self.stats.max, self.runs
+        ));
+        line
+    }
+}
+
+/// Runs every entry `runs` times and reports timings for parsing and each part.
+pub fn bench(entries: &[Entry], args: &Args, runs: usize) -> Result<Results<BenchResult>> {
+    let mut results = vec![];
+    let skipped = with_inputs(entries, args, |entry, input| {
+        let mut parse = vec![];
+        let mut parts: Vec<(u32, Vec<Duration>)> = vec![];
+        for _ in 0..runs {
+            let run = entry
+                .runner(args.strict)
+                .run(input, args.part)
+                .with_context(|| format!("Day {} failed", entry.day))?;
+            parse.push(run.parse);
+            for (i, p) in run.parts.iter().enumerate() {
+                match parts.get_mut(i) {
+                    Some((_, samples)) => samples.push(p.elapsed),
+                    None => parts.push((p.part, vec![p.elapsed])),
+                }
+            }
+        }
+
+        let result = |part, samples: &mut Vec<Duration>| BenchResult {
+            day: entry.day,
+            variant: entry.variant,
+            part,
+            runs,
+            stats: Stats::new(samples),
+        };
+        results.push(result(None, &mut parse));
+        for (part, samples) in &mut parts {
+            results.push(result(Some(*part), samples));
+        }
+        Ok(())
+    })?;
+    Ok((results, skipped))
+}
+
+/// Checks every result against the known answers and returns how many failed.
+pub fn verify(results: &mut [PartResult], answers: &Answers) -> usize {
+    for result in results.iter_mut() {
+        result.status = Some(answers.check(result.day, result.part, &result.answer));
+    }
+    results
+        .iter()
+        .filter(|r| matches!(r.status, Some(Status::Fail { .. })))
+        .count()
+}
+
+pub fn summary(results: &[PartResult], skipped: &[Skipped]) -> String {
+    let count = |f: fn(&Status) -> bool| {
+        results
+            .iter()
+            .filter(|r| r.status.as_ref().is_some_and(f))
+            .count()
+    };
+    let mut summary = format!(
+        "{} passed, {} failed, {} missing",
+        count(|s| *s == Status::Pass),
+        count(|s| matches!(s, Status::Fail { .. })),
+        count(|s| *s == Status::Missing)
+    );
+    if !skipped.is_empty() {
+        summary.push_str(&format!(", {} day(s) skipped", skipped.len()));
+    }
+    summary
+}
+
+/// Answers from every implementation of one day's part.
+#[derive(Debug, PartialEq)]
+pub struct Comparison<'a> {
+    pub day: u32,
+    pub part: u32,
+    pub results: Vec<&'a PartResult>,
+}
+
+impl Comparison<'_> {
+    pub fn agree(&self) -> bool {
+        self.results
+            .windows(2)
+            .all(|pair| pair[0].answer == pair[1].answer)
+    }
+
+    /// Variants ordered from fastest to slowest, counting parsing and solving.
+    pub fn by_speed(&self) -> Vec<&PartResult> {
+        let mut results = self.results.clone();
+        results.sort_by_key(|r| r.parse + r.elapsed);
+        results
+    }
+
+    pub fn format(&self) -> String {
+        let name = |r: &PartResult| r.variant.unwrap_or("default");
+        let head = format!("[Day {:>2}][Part {}]", self.day, self.part);
+
+        if !self.agree() {
+            let answers: Vec<_> = self
+                .results
+                .iter()
+                .map(|r| format!("{} = {}", name(r), r.answer))
+                .collect();
+            return format!("{} variants DISAGREE: {}", head, answers.join(", "));
+        }
+
+        let timings: Vec<_> = self
+            .by_speed()
+            .iter()
+            .map(|r| format!("{} ({:.2?})", name(r), r.parse + r.elapsed))
+            .collect();
+        format!("{} variants agree, fastest: {}", head, timings.join(", "))
+    }
+}
+
+/// Groups the results of days with several implementations by part.
+pub fn compare(results: &[PartResult]) -> Vec<Comparison<'_>> {
+    let mut comparisons: Vec<Comparison> = vec![];
+    for result in results {
+        match comparisons
+            .iter_mut()
+            .find(|c| c.day == result.day && c.part == result.part)
+        {
+            Some(comparison) => comparison.results.push(result),
+            None => comparisons.push(Comparison {
+                day: result.day,
+                part: result.part,
+                results: vec![result],
+            }),
+        }
+    }
+    comparisons.retain(|c| c.results.len() > 1);
+    comparisons
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+impl PartResult {
+    pub fn to_json(&self) -> String {
+        let variant = self.variant.map_or("null".to_owned(), json_string);
+        let answer = match &self.answer {
+            Answer::Number(n) => n.to_string(),
+            // JSON numbers have no size limit, though readers may lose precision
+            Answer::Wide(n) => n.to_string(),
+            Answer::Text(s) => json_string(s),
+        };
+        let status = match &self.status {
+            None => "null".to_owned(),
+            Some(Status::Pass) => json_string("pass"),
+            Some(Status::Fail { .. }) => json_string("fail"),
+            Some(Status::Missing) => json_string("missing"),
+        };
+        let notes: Vec<_> = self.notes.iter().map(|n| json_string(n)).collect();
+        format!(
+            "{{\"day\":{},\"variant\":{},\"part\":{},\"answer\":{},\"parse_ns\":{},\"elapsed_ns\":{},\"status\":{},\"notes\":[{}]}}",
+            self.day,
+            variant,
+            self.part,
+            answer,
+            self.parse.as_nanos(),
+            self.elapsed.as_nanos(),
+            status,
+            notes.join(",")
+        )
+    }
+}
+
+pub fn to_json(results: &[PartResult], skipped: &[Skipped]) -> String {
+    let objects: Vec<_> = results
+        .iter()
+        .map(PartResult::to_json)
+        .chain(skipped.iter().map(Skipped::to_json))
+        .collect();
+    format!("[{}]", objects.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_test() {
+        let mut result = PartResult {
+            day: 5,
+            variant: None,
+            part: 1,
+            answer: Answer::from("CMZ"),
+            parse: Duration::from_micros(40),
+            elapsed: Duration::from_nanos(3100),
+            status: None,
+            notes: vec![],
+        };
+        assert_eq!(
+            "[Day  5][Part 1] - CMZ [parse 40.00µs, part 3.10µs]",
+            result.format()
+        );
+
+        result.day = 12;
+        result.variant = Some("better");
+        assert_eq!(
+            "[Day 12][Part 1] - CMZ (better) [parse 40.00µs, part 3.10µs]",
+            result.format()
+        );
+
+        result.status = Some(Status::Fail {
+            expected: Answer::from("MCD"),
+        });
+        assert_eq!(
+            "[Day 12][Part 1] - CMZ (better) [parse 40.00µs, part 3.10µs] [FAIL, expected MCD]",
+            result.format()
+        );
+
+        result.status = None;
+        result.notes = vec!["normalized 2 CRLF line endings".to_owned()];
+        assert_eq!(
+            "[Day 12][Part 1] - CMZ (better) [parse 40.00µs, part 3.10µs] [normalized 2 CRLF line endings]",
+            result.format()
+        );
+    }
+
+    #[test]
+    fn verify_test() {
+        let result = |part, answer: i64| PartResult {
+            day: 1,
+            variant: None,
+            part,
+            answer: Answer::Number(answer),
+            parse: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            status: None,
+            notes: vec![],
+        };
+        let mut results = vec![result(1, 24000), result(2, 45001)];
+
+        let answers = Answers::parse("[day1]\npart1 = 24000\npart2 = 45000").unwrap();
+        assert_eq!(1, verify(&mut results, &answers));
+        assert_eq!(Some(Status::Pass), results[0].status);
+        assert_eq!("1 passed, 1 failed, 0 missing", summary(&results, &[]));
+
+        assert_eq!(0, verify(&mut results, &Answers::default()));
+        assert_eq!("0 passed, 0 failed, 2 missing", summary(&results, &[]));
+    }
+
+    #[test]
+    fn skips_days_without_input() {
+        let dir = std::env::temp_dir().join("aoc-runner-skip-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("2022")).unwrap();
+        std::fs::write(dir.join("2022/day4.txt"), "2-4,6-8\n").unwrap();
+
+        let entries = |days: &[u32]| -> Vec<Entry> {
+            crate::registry::registry()
+                .into_iter()
+                .filter(|entry| days.contains(&entry.day))
+                .collect()
+        };
+        let args = Args {
+            days: 3..=6,
+            input_dir: Some(dir.clone()),
+            ..Args::default()
+        };
+
+        let (results, skipped) = run(&entries(&[4, 6]), &args).unwrap();
+        assert_eq!(
+            vec![4, 4],
+            results.iter().map(|r| r.day).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![6], skipped.iter().map(|s| s.day).collect::<Vec<_>>());
+        assert!(skipped[0]
+            .format()
+            .starts_with("[Day  6] skipped: Need to provide puzzle input"));
+
+        // the empty placeholder from `aoc new` is no input either
+        std::fs::write(dir.join("2022/day6.txt"), "").unwrap();
+        let (results, skipped) = run(&entries(&[4, 6]), &args).unwrap();
+        assert_eq!((2, 1), (results.len(), skipped.len()));
+
+        let (results, skipped) = stream(&entries(&[3, 4]), &args).unwrap();
+        assert_eq!(2, results.len());
+        assert_eq!(3, skipped[0].day);
+
+        // with nothing to run, the run fails
+        assert!(run(&entries(&[6]), &args).is_err());
+
+        // a day asked for on its own, or an input given explicitly, has to be there
+        let single = Args {
+            days: 6..=6,
+            input_dir: Some(dir.clone()),
+            ..Args::default()
+        };
+        assert!(run(&entries(&[6]), &single).is_err());
+        let explicit = Args {
+            input: InputSource::File(dir.join("missing.txt")),
+            ..Args::default()
+        };
+        assert!(run(&entries(&[4, 6]), &explicit).is_err());
+    }
+
+    #[test]
+    fn stats_test() {
+        let mut samples: Vec<_> = [5, 1, 4, 2, 3].map(Duration::from_millis).into();
+        assert_eq!(
+            Stats {
+                min: Duration::from_millis(1),
+                median: Duration::from_millis(3),
+                max: Duration::from_millis(5),
+            },
+            Stats::new(&mut samples)
+        );
+    }
+
+    #[test]
+    fn bench_format_test() {
+        let stats = Stats {
+            min: Duration::from_micros(1),
+            median: Duration::from_micros(2),
+            max: Duration::from_micros(30),
+        };
+        let mut result = BenchResult {
+            day: 2,
+            variant: Some("better"),
+            part: Some(1),
+            runs: 10,
+            stats,
+        };
+        assert_eq!(
+            "[Day  2][Part 1] (better) min 1.00µs, median 2.00µs, max 30.00µs over 10 runs",
+            result.format()
+        );
+
+        result.variant = None;
+        result.part = None;
+        assert_eq!(
+            "[Day  2][Parse ] min 1.00µs, median 2.00µs, max 30.00µs over 10 runs",
+            result.format()
+        );
+    }
+
+    #[test]
+    fn json_test() {
+        let mut results = vec![
+            PartResult {
+                day: 5,
+                variant: None,
+                part: 1,
+                answer: Answer::from("C\"M\\Z\n"),
+                parse: Duration::from_nanos(40),
+                elapsed: Duration::from_nanos(3100),
+                status: None,
+                notes: vec![],
+            },
+            PartResult {
+                day: 2,
+                variant: Some("better"),
+                part: 2,
+                answer: Answer::Number(12),
+                parse: Duration::ZERO,
+                elapsed: Duration::from_nanos(7),
+                status: Some(Status::Fail {
+                    expected: Answer::Number(15),
+                }),
+                notes: vec!["a \"note\"".to_owned()],
+            },
+        ];
+        assert_eq!(
+            concat!(
+                r#"[{"day":5,"variant":null,"part":1,"answer":"C\"M\\Z\n","parse_ns":40,"elapsed_ns":3100,"status":null,"notes":[]},"#,
+                r#"{"day":2,"variant":"better","part":2,"answer":12,"parse_ns":0,"elapsed_ns":7,"status":"fail","notes":["a \"note\""]}]"#
+            ),
+            to_json(&results, &[])
+        );
+
+        results.truncate(1);
+        results[0].answer = Answer::number(u64::MAX as u128 + 1);
+        assert!(to_json(&results, &[]).contains(r#""answer":18446744073709551616,"#));
+
+        results.clear();
+        assert_eq!("[]", to_json(&results, &[]));
+
+        let skipped = Skipped {
+            day: 7,
+            reason: "no input".to_owned(),
+        };
+        assert_eq!(
+            r#"[{"day":7,"variant":null,"part":null,"answer":null,"parse_ns":null,"elapsed_ns":null,"status":"skipped","notes":["no input"]}]"#,
+            to_json(&results, &[skipped])
+        );
+    }
+
+    #[test]
+    fn compare_test() {
+        let result = |variant, part, answer: i64, micros| PartResult {
+            day: 2,
+            variant,
+            part,
+            answer: Answer::Number(answer),
+            parse: Duration::from_micros(1),
+            elapsed: Duration::from_micros(micros),
+            status: None,
+            notes: vec![],
+        };
+        let results = vec![
+            result(None, 1, 15, 9),
+            result(None, 2, 12, 9),
+            result(Some("better"), 1, 15, 3),
+            result(Some("better"), 2, 13, 3),
+        ];
+
+        let comparisons = compare(&results);
+        assert_eq!(2, comparisons.len());
+
+        assert!(comparisons[0].agree());
+        assert_eq!(Some("better"), comparisons[0].by_speed()[0].variant);
+        assert_eq!(
+            "[Day  2][Part 1] variants agree, fastest: better (4.00µs), default (10.00µs)",
+            comparisons[0].format()
+        );
+
+        assert!(!comparisons[1].agree());
+        assert_eq!(
+            "[Day  2][Part 2] variants DISAGREE: default = 12, better = 13",
+            comparisons[1].format()
+        );
+
+        assert!(compare(&results[..2]).is_empty());
+    }
+}
@@ -0,0 +1,269 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::registry::YEAR;
+
+fn template(day: u32) -> String {
+    format!(
+        r#"use anyhow::Result;
+
+use crate::parse::{{parse_lines, ParseError}};
+use crate::solution::Solution;
+
+fn parse_line(line: &str) -> Result<String, ParseError> {{
+    Ok(line.to_owned())
+}}
+
+fn part1(lines: &[String]) -> u32 {{
+    lines.len() as u32
+}}
+
+fn part2(_lines: &[String]) -> u32 {{
+    0
+}}
+
+pub struct Day{day};
+
+impl Solution for Day{day} {{
+    type Input = Vec<String>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {{
+        Ok(parse_lines(input, parse_line)?)
+    }}
+
+    fn part1(&self, lines: &Self::Input) -> Result<Self::Part1> {{
+        Ok(part1(lines))
+    }}
+
+    fn part2(&self, lines: &Self::Input) -> Result<Self::Part2> {{
+        Ok(part2(lines))
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../examples/day{day}/example.txt");
+
+    #[test]
+    fn part1_test() {{
+        let lines = parse_lines(EXAMPLE, parse_line).unwrap();
+        assert_eq!(0, part1(&lines));
+    }}
+
+    #[test]
+    fn part2_test() {{
+        let lines = parse_lines(EXAMPLE, parse_line).unwrap();
+        assert_eq!(0, part2(&lines));
+    }}
+}}
+"#
+    )
+}
+
+/// Adds `mod day{day};` where rustfmt would sort it among the other days.
+fn add_module(main: &str, day: u32) -> Result<String> {
+    let module = format!("day{}", day);
+    let mut lines: Vec<&str> = main.lines().collect();
+
+    let days: Vec<(usize, &str)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let name = line.strip_prefix("mod ")?.strip_suffix(';')?;
+            name.starts_with("day").then_some((i, name))
+        })
+        .collect();
+    if days.iter().any(|&(_, name)| name == module) {
+        bail!("`mod {};` is already declared", module);
+    }
+
+    let position = match days.iter().find(|&&(_, name)| name > module.as_str()) {
+        Some(&(i, _)) => i,
+        None => days.last().map_or(0, |&(i, _)| i + 1),
+    };
+    let declaration = format!("mod {};", module);
+    lines.insert(position, &declaration);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Imports the new module in the registry and registers it in day order.
+fn add_entry(registry: &str, day: u32) -> Result<String> {
+    let module = format!("day{}", day);
+
+    let use_start = registry
+        .find("use crate::{")
+        .context("Registry has no `use crate::{...}` for the days")?;
+    let use_end = use_start
+        + registry[use_start..]
+            .find("};")
+            .context("Unterminated `use crate::{...}`")?;
+    let mut modules: Vec<&str> = registry[use_start + "use crate::{".len()..use_end]
+        .split(',')
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .collect();
+    modules.push(&module);
+    modules.sort();
+    let imports = format!("use crate::{{{}}}", modules.join(", "));
+
+    let body = registry
+        .find("pub fn registry()")
+        .context("Registry has no `registry()` function")?;
+    let end = body
+        + registry[body..]
+            .find("\n    ]\n")
+            .context("Registry has no list of entries")?;
+    let later = registry[body..end]
+        .match_indices("        Entry::new(")
+        .map(|(i, _)| body + i)
+        .find(|&i| {
+            let rest = &registry[i + "        Entry::new(".len()..];
+            rest.split(',')
+                .next()
+                .and_then(|n| n.trim().parse::<u32>().ok())
+                .is_some_and(|n| n > day)
+        });
+    let insert_at = later.unwrap_or(end + 1);
+    let entry = format!("        Entry::new({}, {}::Day{}),\n", day, module, day);
+
+    Ok(format!(
+        "{}{}{}{}{}",
+        &registry[..use_start],
+        imports,
+        &registry[use_end + 1..insert_at],
+        entry,
+        &registry[insert_at..]
+    ))
+}
+
+/// Creates the module, registry entry, example fixture and input placeholder
+/// for a new day, returning the files that were written.
+pub fn new_day(root: &Path, day: u32) -> Result<Vec<PathBuf>> {
+    let main_path = root.join("src/main.rs");
+    let registry_path = root.join("src/registry.rs");
+    let module_path = root.join(format!("src/day{}.rs", day));
+    let input_path = root
+        .join("input")
+        .join(YEAR.to_string())
+        .join(format!("day{}.txt", day));
+    let example_dir = root.join("examples").join(format!("day{}", day));
+
+    if module_path.exists() {
+        bail!("{} already exists", module_path.display());
+    }
+    let main = std::fs::read_to_string(&main_path)
+        .with_context(|| format!("Run from the repository root, no {}", main_path.display()))?;
+    let registry = std::fs::read_to_string(&registry_path)
+        .with_context(|| format!("Could not read {}", registry_path.display()))?;
+
+    let main = add_module(&main, day)?;
+    let registry = add_entry(&registry, day)?;
+
+    std::fs::write(&module_path, template(day))?;
+    std::fs::write(&main_path, main)?;
+    std::fs::write(&registry_path, registry)?;
+
+    let mut written = vec![module_path, main_path, registry_path];
+    if !example_dir.exists() {
+        std::fs::create_dir_all(&example_dir)?;
+        let example = example_dir.join("example.txt");
+        let answers = example_dir.join("example.toml");
+        std::fs::write(&example, "")?;
+        std::fs::write(&answers, "# part1 = \n# part2 = \n")?;
+        written.extend([example, answers]);
+    }
+    if !input_path.exists() {
+        if let Some(parent) = input_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&input_path, "")?;
+        written.push(input_path);
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN: &str = "mod cli;
+mod day1;
+mod day2;
+mod day2_better;
+mod day3;
+#[cfg(test)]
+mod generate;
+
+fn main() {}
+";
+
+    const REGISTRY: &str = "use crate::solution::Runner;
+use crate::{day1, day2, day2_better, day3};
+
+pub fn registry() -> Vec<Entry> {
+    vec![
+        Entry::new(1, day1::Day1),
+        Entry::new(2, day2::Day2),
+        Entry::new(2, day2_better::Day2Better).variant(\"better\"),
+        Entry::new(3, day3::Day3),
+    ]
+}
+";
+
+    #[test]
+    fn add_module_test() {
+        let main = add_module(MAIN, 7).unwrap();
+        assert!(main.contains("mod day3;\nmod day7;\n#[cfg(test)]"));
+
+        let main = add_module(MAIN, 10).unwrap();
+        assert!(main.contains("mod day1;\nmod day10;\nmod day2;"));
+
+        assert!(add_module(MAIN, 3).is_err());
+    }
+
+    #[test]
+    fn add_entry_test() {
+        let registry = add_entry(REGISTRY, 7).unwrap();
+        assert!(registry.contains("use crate::{day1, day2, day2_better, day3, day7};"));
+        assert!(registry.contains(
+            "        Entry::new(3, day3::Day3),\n        Entry::new(7, day7::Day7),\n    ]\n}"
+        ));
+
+        let registry = add_entry(&REGISTRY.replace('3', "5"), 4).unwrap();
+        assert!(registry.contains("use crate::{day1, day2, day2_better, day4, day5};"));
+        assert!(registry
+            .contains("        Entry::new(4, day4::Day4),\n        Entry::new(5, day5::Day5),\n"));
+    }
+
+    #[test]
+    fn new_day_test() {
+        let root = std::env::temp_dir().join("aoc-scaffold-test");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/main.rs"), MAIN).unwrap();
+        std::fs::write(root.join("src/registry.rs"), REGISTRY).unwrap();
+
+        let written = new_day(&root, 7).unwrap();
+        assert_eq!(6, written.len());
+
+        let module = std::fs::read_to_string(root.join("src/day7.rs")).unwrap();
+        assert!(module.contains("pub struct Day7;"));
+        assert!(module.contains("impl Solution for Day7 {"));
+        assert!(module.contains("#[cfg(test)]"));
+        assert_eq!(
+            "",
+            std::fs::read_to_string(root.join("input/2022/day7.txt")).unwrap()
+        );
+        assert!(module.contains("include_str!(\"../examples/day7/example.txt\")"));
+        assert!(root.join("examples/day7/example.toml").exists());
+
+        assert!(new_day(&root, 7).is_err());
+    }
+}
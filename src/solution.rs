@@ -0,0 +1,312 @@
+use std::fmt::{self, Display};
+use std::io::BufRead;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use num::BigInt;
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Answer {
+    Number(i64),
+    /// A number that doesn't fit in an `i64`. [`Answer::number`] keeps every
+    /// number that does fit a `Number`, so equal answers compare equal.
+    Wide(BigInt),
+    Text(String),
+}
+
+impl Answer {
+    /// A number of any width, as a `Wide` one when it doesn't fit in an `i64`.
+    /// Anything that isn't a number becomes text.
+    pub fn number(n: impl Display) -> Answer {
+        let text = n.to_string();
+        if let Ok(n) = text.parse() {
+            return Answer::Number(n);
+        }
+        text.parse().map_or(Answer::Text(text), Answer::Wide)
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Wide(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Self {
+        Answer::Number(n.into())
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Self {
+        Answer::Number(n.into())
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Number(n)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_owned())
+    }
+}
+
+/// A day's puzzle: the input is parsed once and both parts are solved from it.
+pub trait Solution {
+    type Input;
+    type Part1: Into<Answer>;
+    type Part2: Into<Answer>;
+
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+    fn part1(&self, input: &Self::Input) -> Result<Self::Part1>;
+    fn part2(&self, input: &Self::Input) -> Result<Self::Part2>;
+
+    /// A report about the parsed input for `--stats`, for days that have one.
+    fn stats(&self, _input: &Self::Input) -> Option<String> {
+        None
+    }
+
+    /// Anything about the parsed input worth pointing out next to the answers.
+    fn notes(&self, _input: &Self::Input) -> Vec<String> {
+        vec![]
+    }
+}
+
+/// The answers from running a solution once, along with how long each step took.
+#[derive(Debug)]
+pub struct Run {
+    pub parse: Duration,
+    pub parts: Vec<PartRun>,
+    /// See [`Solution::notes`].
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct PartRun {
+    pub part: u32,
+    pub answer: Answer,
+    pub elapsed: Duration,
+}
+
+#[cfg(test)]
+impl Run {
+    pub fn answers(&self) -> Vec<(u32, Answer)> {
+        self.parts
+            .iter()
+            .map(|p| (p.part, p.answer.clone()))
+            .collect()
+    }
+}
+
+/// Object safe wrapper around [`Solution`] so every day can live in one registry.
+pub trait Runner {
+    /// Parses `input` and solves the requested part, or both when `part` is `None`.
+    fn run(&self, input: &str, part: Option<u32>) -> Result<Run>;
+
+    /// Parses `input` and reports on it, if the solution has a report.
+    fn stats(&self, input: &str) -> Result<Option<String>>;
+}
+
+fn timed<T>(f: impl FnOnce() -> Result<T>) -> Result<(T, Duration)> {
+    let start = Instant::now();
+    let value = f()?;
+    Ok((value, start.elapsed()))
+}
+
+impl<S: Solution> Runner for S {
+    fn run(&self, input: &str, part: Option<u32>) -> Result<Run> {
+        let (input, parse) = timed(|| self.parse(input))?;
+
+        let mut parts = vec![];
+        if part.is_none_or(|p| p == 1) {
+            let (answer, elapsed) = timed(|| self.part1(&input))?;
+            parts.push(PartRun {
+                part: 1,
+                answer: answer.into(),
+                elapsed,
+            });
+        }
+        if part.is_none_or(|p| p == 2) {
+            let (answer, elapsed) = timed(|| self.part2(&input))?;
+            parts.push(PartRun {
+                part: 2,
+                answer: answer.into(),
+                elapsed,
+            });
+        }
+
+        Ok(Run {
+            parse,
+            parts,
+            notes: self.notes(&input),
+        })
+    }
+
+    fn stats(&self, input: &str) -> Result<Option<String>> {
+        Ok(Solution::stats(self, &self.parse(input)?))
+    }
+}
+
+/// A solution that reads its input a line at a time instead of all at once,
+/// so it runs in constant memory however large the input is. Each part reads
+/// the whole input again.
+pub trait Streaming {
+    type Part1: Into<Answer>;
+    type Part2: Into<Answer>;
+
+    fn stream_part1(&self, input: &mut dyn BufRead) -> Result<Self::Part1>;
+    fn stream_part2(&self, input: &mut dyn BufRead) -> Result<Self::Part2>;
+}
+
+/// Opens a fresh stream of the puzzle input.
+pub type Open<'a> = dyn FnMut() -> Result<Box<dyn BufRead>> + 'a;
+
+/// Object safe wrapper around [`Streaming`], like [`Runner`] is for [`Solution`].
+pub trait StreamRunner {
+    /// Solves the requested part, or both when `part` is `None`, opening the
+    /// input once per part. Parsing happens as each part reads, so `parse`
+    /// is always zero.
+    fn stream(&self, open: &mut Open, part: Option<u32>) -> Result<Run>;
+}
+
+impl<S: Streaming> StreamRunner for S {
+    fn stream(&self, open: &mut Open, part: Option<u32>) -> Result<Run> {
+        let mut parts = vec![];
+        if part.is_none_or(|p| p == 1) {
+            let mut input = open()?;
+            let (answer, elapsed) = timed(|| self.stream_part1(&mut input))?;
+            parts.push(PartRun {
+                part: 1,
+                answer: answer.into(),
+                elapsed,
+            });
+        }
+        if part.is_none_or(|p| p == 2) {
+            let mut input = open()?;
+            let (answer, elapsed) = timed(|| self.stream_part2(&mut input))?;
+            parts.push(PartRun {
+                part: 2,
+                answer: answer.into(),
+                elapsed,
+            });
+        }
+
+        Ok(Run {
+            parse: Duration::ZERO,
+            parts,
+            notes: vec![],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::for_each_line;
+
+    struct Lines;
+
+    impl Solution for Lines {
+        type Input = Vec<String>;
+        type Part1 = u32;
+        type Part2 = String;
+
+        fn parse(&self, input: &str) -> Result<Self::Input> {
+            Ok(input.lines().map(str::to_owned).collect())
+        }
+
+        fn part1(&self, input: &Self::Input) -> Result<Self::Part1> {
+            Ok(input.len() as u32)
+        }
+
+        fn part2(&self, input: &Self::Input) -> Result<Self::Part2> {
+            Ok(input.concat())
+        }
+    }
+
+    #[test]
+    fn run_selected_parts() {
+        let input = "ab\ncd";
+        assert_eq!(
+            vec![(1, Answer::Number(2)), (2, Answer::from("abcd"))],
+            Lines.run(input, None).unwrap().answers()
+        );
+        assert_eq!(
+            vec![(1, Answer::Number(2))],
+            Lines.run(input, Some(1)).unwrap().answers()
+        );
+        assert_eq!(
+            vec![(2, Answer::from("abcd"))],
+            Lines.run(input, Some(2)).unwrap().answers()
+        );
+    }
+
+    impl Streaming for Lines {
+        type Part1 = u32;
+        type Part2 = String;
+
+        fn stream_part1(&self, input: &mut dyn BufRead) -> Result<Self::Part1> {
+            let mut count = 0;
+            for_each_line(input, |_| {
+                count += 1;
+                Ok(())
+            })?;
+            Ok(count)
+        }
+
+        fn stream_part2(&self, input: &mut dyn BufRead) -> Result<Self::Part2> {
+            let mut all = String::new();
+            for_each_line(input, |line| {
+                all.push_str(line);
+                Ok(())
+            })?;
+            Ok(all)
+        }
+    }
+
+    #[test]
+    fn stream_selected_parts() {
+        let mut opened = 0;
+        let mut open = || -> Result<Box<dyn BufRead>> {
+            opened += 1;
+            Ok(Box::new("ab\ncd".as_bytes()))
+        };
+        let run = Lines.stream(&mut open, None).unwrap();
+        assert_eq!(Lines.run("ab\ncd", None).unwrap().answers(), run.answers());
+        assert_eq!(Duration::ZERO, run.parse);
+
+        let run = Lines.stream(&mut open, Some(2)).unwrap();
+        assert_eq!(vec![(2, Answer::from("abcd"))], run.answers());
+        assert_eq!(3, opened, "every part reads a fresh stream");
+    }
+
+    #[test]
+    fn answer_display() {
+        assert_eq!("24000", Answer::from(24000u32).to_string());
+        assert_eq!("-3", Answer::from(-3).to_string());
+        assert_eq!("CMZ", Answer::from("CMZ".to_owned()).to_string());
+
+        assert_eq!(Answer::Number(-3), Answer::number(-3i8));
+        assert_eq!(Answer::Number(i64::MAX), Answer::number(i64::MAX as u128));
+        let wide = Answer::number(i64::MAX as u128 + 1);
+        assert_eq!(Answer::Wide("9223372036854775808".parse().unwrap()), wide);
+        assert_eq!("9223372036854775808", wide.to_string());
+        assert_eq!(Answer::from("CMZ"), Answer::number("CMZ"));
+    }
+}
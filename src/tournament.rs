@@ -0,0 +1,351 @@
+//! Bots that play a [`Game`] against each other, and a league to rank them.
+
+use std::cmp::{Ordering, Reverse};
+use std::fmt::{self, Display};
+
+use crate::analysis;
+use crate::game::{Game, Move, Outcome};
+
+/// Someone who picks moves, knowing how every earlier round of the match
+/// went as `(mine, theirs)`. A player can remember what it has already seen
+/// of a match; a new match starts with an empty history.
+pub trait Player {
+    fn name(&self, game: &Game) -> String;
+
+    fn play(&mut self, game: &Game, history: &[(Move, Move)]) -> Move;
+}
+
+/// Plays the same move every round.
+pub struct Always(pub Move);
+
+impl Player for Always {
+    fn name(&self, game: &Game) -> String {
+        format!("always {}", game.name(self.0))
+    }
+
+    fn play(&mut self, _: &Game, _: &[(Move, Move)]) -> Move {
+        self.0
+    }
+}
+
+/// Plays every move in turn, in cycle order.
+pub struct Cycle;
+
+impl Player for Cycle {
+    fn name(&self, _: &Game) -> String {
+        "cycle".to_owned()
+    }
+
+    fn play(&mut self, game: &Game, history: &[(Move, Move)]) -> Move {
+        game.moves()
+            .nth(history.len() % game.moves().count())
+            .unwrap()
+    }
+}
+
+/// Plays the best response to how often the opponent has played each move
+/// so far.
+#[derive(Default)]
+pub struct FrequencyCounter {
+    /// How often the opponent played each move in the rounds counted so far.
+    counts: Vec<f64>,
+    /// How many rounds of the current match are in `counts`.
+    counted: usize,
+}
+
+impl Player for FrequencyCounter {
+    fn name(&self, _: &Game) -> String {
+        "frequency counter".to_owned()
+    }
+
+    fn play(&mut self, game: &Game, history: &[(Move, Move)]) -> Move {
+        if history.len() < self.counted {
+            // a new match
+            self.counts.clear();
+            self.counted = 0;
+        }
+        self.counts.resize(game.moves().count(), 0.0);
+        for &(_, theirs) in &history[self.counted..] {
+            self.counts[theirs.0] += 1.0;
+        }
+        self.counted = history.len();
+
+        if history.is_empty() {
+            return Move(0);
+        }
+        analysis::best_response(game, &self.counts).unwrap().0
+    }
+}
+
+/// Keeps a move that just won, and otherwise switches to the move that would
+/// have beaten the opponent's last one.
+pub struct WinStayLoseShift;
+
+impl Player for WinStayLoseShift {
+    fn name(&self, _: &Game) -> String {
+        "win-stay lose-shift".to_owned()
+    }
+
+    fn play(&mut self, game: &Game, history: &[(Move, Move)]) -> Move {
+        match history.last() {
+            None => Move(0),
+            Some(&(mine, theirs)) if game.outcome(mine, theirs) == Outcome::Win => mine,
+            Some(&(_, theirs)) => game.fit_strategy(theirs, Outcome::Win),
+        }
+    }
+}
+
+/// Plays a fixed list of moves, like a column of a strategy guide, starting
+/// over when it runs out.
+pub struct Replay {
+    pub name: String,
+    pub moves: Vec<Move>,
+}
+
+impl Player for Replay {
+    fn name(&self, _: &Game) -> String {
+        self.name.clone()
+    }
+
+    fn play(&mut self, _: &Game, history: &[(Move, Move)]) -> Move {
+        self.moves[history.len() % self.moves.len()]
+    }
+}
+
+/// The bots that don't need anything to play from.
+pub fn bots() -> Vec<Box<dyn Player>> {
+    vec![
+        Box::new(Always(Move(0))),
+        Box::new(Cycle),
+        Box::new(FrequencyCounter::default()),
+        Box::new(WinStayLoseShift),
+    ]
+}
+
+/// Plays `rounds` rounds of `first` against `second` and returns their scores.
+pub fn play_match(
+    game: &Game,
+    first: &mut dyn Player,
+    second: &mut dyn Player,
+    rounds: usize,
+) -> (u64, u64) {
+    let mut history: Vec<(Move, Move)> = Vec::with_capacity(rounds);
+    let mut mirrored: Vec<(Move, Move)> = Vec::with_capacity(rounds);
+    let mut scores = (0, 0);
+    for _ in 0..rounds {
+        let a = first.play(game, &history);
+        let b = second.play(game, &mirrored);
+        scores.0 += u64::from(game.score_round((b, a)));
+        scores.1 += u64::from(game.score_round((a, b)));
+        history.push((a, b));
+        mirrored.push((b, a));
+    }
+    scores
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Standing {
+    pub name: String,
+    pub won: u32,
+    pub drawn: u32,
+    pub lost: u32,
+    /// Every round score from every match.
+    pub score: u64,
+}
+
+impl Standing {
+    /// Three for a match won and one for a draw.
+    pub fn points(&self) -> u32 {
+        3 * self.won + self.drawn
+    }
+}
+
+/// Standings after every player has played every other one, best first.
+#[derive(Debug, PartialEq)]
+pub struct League(pub Vec<Standing>);
+
+/// Plays a match of `rounds` rounds between every pair of `players`.
+pub fn tournament(game: &Game, players: &mut [Box<dyn Player>], rounds: usize) -> League {
+    let mut standings: Vec<Standing> = players
+        .iter()
+        .map(|player| Standing {
+            name: player.name(game),
+            ..Standing::default()
+        })
+        .collect();
+
+    for i in 0..players.len() {
+        for j in i + 1..players.len() {
+            let (left, right) = players.split_at_mut(j);
+            let (a, b) = play_match(game, left[i].as_mut(), right[0].as_mut(), rounds);
+            standings[i].score += a;
+            standings[j].score += b;
+            match a.cmp(&b) {
+                Ordering::Greater => {
+                    standings[i].won += 1;
+                    standings[j].lost += 1;
+                }
+                Ordering::Less => {
+                    standings[i].lost += 1;
+                    standings[j].won += 1;
+                }
+                Ordering::Equal => {
+                    standings[i].drawn += 1;
+                    standings[j].drawn += 1;
+                }
+            }
+        }
+    }
+
+    // stable, so players level on both stay in the order they were given
+    standings.sort_by_key(|s| Reverse((s.points(), s.score)));
+    League(standings)
+}
+
+impl Display for League {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.0.iter().map(|s| s.name.len()).max().unwrap_or(0);
+        write!(
+            f,
+            " #  {:width$}   W   D   L  points  score",
+            "player",
+            width = width
+        )?;
+        for (i, standing) in self.0.iter().enumerate() {
+            write!(
+                f,
+                "\n{:>2}  {:width$} {:>3} {:>3} {:>3} {:>7} {:>6}",
+                i + 1,
+                standing.name,
+                standing.won,
+                standing.drawn,
+                standing.lost,
+                standing.points(),
+                standing.score,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROCK: Move = Move(0);
+    const PAPER: Move = Move(1);
+    const SCISSORS: Move = Move(2);
+
+    fn moves(player: &mut dyn Player, against: &mut dyn Player, rounds: usize) -> Vec<Move> {
+        let game = Game::rock_paper_scissors();
+        let mut history = vec![];
+        for _ in 0..rounds {
+            let mine = player.play(&game, &history);
+            let mirrored: Vec<_> = history.iter().map(|&(a, b)| (b, a)).collect();
+            let theirs = against.play(&game, &mirrored);
+            history.push((mine, theirs));
+        }
+        history.into_iter().map(|(mine, _)| mine).collect()
+    }
+
+    #[test]
+    fn bots_test() {
+        let rock = &mut Always(ROCK);
+        assert_eq!(
+            vec![ROCK, PAPER, SCISSORS, ROCK],
+            moves(&mut Cycle, rock, 4)
+        );
+        assert_eq!(
+            vec![ROCK, PAPER, PAPER],
+            moves(&mut WinStayLoseShift, rock, 3)
+        );
+
+        let counter = &mut FrequencyCounter::default();
+        assert_eq!(vec![ROCK, PAPER, PAPER], moves(counter, rock, 3));
+        // a new match starts counting again
+        assert_eq!(
+            vec![ROCK, SCISSORS, SCISSORS],
+            moves(counter, &mut Always(PAPER), 3)
+        );
+
+        // against cycle, win-stay lose-shift plays what beat the last move
+        assert_eq!(
+            vec![ROCK, PAPER, SCISSORS, ROCK],
+            moves(&mut WinStayLoseShift, &mut Cycle, 4)
+        );
+
+        let mut replay = Replay {
+            name: "guide".to_owned(),
+            moves: vec![SCISSORS, PAPER],
+        };
+        assert_eq!(vec![SCISSORS, PAPER, SCISSORS], moves(&mut replay, rock, 3));
+    }
+
+    #[test]
+    fn play_match_test() {
+        let game = Game::rock_paper_scissors();
+        // paper wins every round for 8, rock loses for 1
+        assert_eq!(
+            (80, 10),
+            play_match(&game, &mut Always(PAPER), &mut Always(ROCK), 10)
+        );
+        // rock draws, then loses twice to paper
+        assert_eq!(
+            (4 + 1 + 1, 4 + 8 + 8),
+            play_match(
+                &game,
+                &mut Always(ROCK),
+                &mut FrequencyCounter::default(),
+                3
+            )
+        );
+    }
+
+    #[test]
+    fn tournament_test() {
+        let game = Game::rock_paper_scissors();
+        let league = tournament(&game, &mut bots(), 100);
+        assert_eq!(4, league.0.len());
+        for standing in &league.0 {
+            assert_eq!(3, standing.won + standing.drawn + standing.lost);
+        }
+        let won: u32 = league.0.iter().map(|s| s.won).sum();
+        let lost: u32 = league.0.iter().map(|s| s.lost).sum();
+        assert_eq!(won, lost);
+        assert!(league
+            .0
+            .windows(2)
+            .all(|w| (w[0].points(), w[0].score) >= (w[1].points(), w[1].score)));
+
+        // always playing rock is easy to read
+        assert_eq!("always rock", league.0[3].name);
+        assert_eq!(0, league.0[3].won);
+    }
+
+    #[test]
+    fn league_display() {
+        let league = League(vec![
+            Standing {
+                name: "cycle".to_owned(),
+                won: 1,
+                drawn: 0,
+                lost: 0,
+                score: 80,
+            },
+            Standing {
+                name: "always rock".to_owned(),
+                won: 0,
+                drawn: 0,
+                lost: 1,
+                score: 10,
+            },
+        ]);
+        assert_eq!(
+            " #  player        W   D   L  points  score
+ 1  cycle         1   0   0       3     80
+ 2  always rock   0   0   1       0     10",
+            league.to_string()
+        );
+    }
+}